@@ -0,0 +1,44 @@
+//! Declares a module for every `src/dayNN.rs` and registers its `DayNN` solution.
+//!
+//! `cargo fmt` can't see modules declared here, `just fmt` formats the days as well.
+
+use std::{env, fmt::Write, fs, path::Path};
+
+fn main() {
+    let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+    println!("cargo::rerun-if-changed={}", src.display());
+
+    let mut days: Vec<u8> = fs::read_dir(&src)
+        .expect("src is readable")
+        .filter_map(|entry| {
+            let name = entry.expect("src is readable").file_name();
+            let name = name.to_str()?;
+            let day = name.strip_prefix("day")?.strip_suffix(".rs")?;
+            (day.len() == 2).then(|| day.parse().ok())?
+        })
+        .collect();
+    days.sort();
+
+    let mut modules = String::new();
+    let mut registry =
+        String::from("/// Every implemented day, in order.\npub static DAYS: &[&dyn Day] = &[\n");
+    for day in days {
+        let path = src.join(format!("day{day:02}.rs"));
+        writeln!(modules, "#[path = {:?}]\nmod day{day:02};", path.display()).unwrap();
+        writeln!(
+            registry,
+            "    &Entry::<crate::day{day:02}::Day{day:02}>::new(),"
+        )
+        .unwrap();
+        writeln!(
+            modules,
+            "const _: () = assert!(\n    <day{day:02}::Day{day:02} as solution::Solution>::DAY == {day},\n    \"Day{day:02}::DAY must be {day}\",\n);"
+        )
+        .unwrap();
+    }
+    registry.push_str("];\n");
+
+    let out = Path::new(&env::var("OUT_DIR").expect("set by cargo")).to_path_buf();
+    fs::write(out.join("days.rs"), modules).expect("OUT_DIR is writable");
+    fs::write(out.join("registry.rs"), registry).expect("OUT_DIR is writable");
+}
@@ -0,0 +1,189 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering::Relaxed},
+};
+
+/// System allocator that keeps count of what goes through it.
+///
+/// Installed as the global allocator with the `count-alloc` feature.
+#[derive(Debug, Default)]
+pub struct Counting {
+    allocs: AtomicUsize,
+    bytes: AtomicUsize,
+    live: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+/// Heap use of one piece of code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Allocs {
+    /// Allocations and reallocations.
+    pub count: usize,
+    /// Bytes requested by them, in total.
+    pub bytes: usize,
+    /// Most bytes live at once on top of what was live before.
+    pub peak: usize,
+}
+
+impl Counting {
+    pub const fn new() -> Self {
+        Self {
+            allocs: AtomicUsize::new(0),
+            bytes: AtomicUsize::new(0),
+            live: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+        }
+    }
+
+    fn allocated(&self, size: usize) {
+        self.allocs.fetch_add(1, Relaxed);
+        self.bytes.fetch_add(size, Relaxed);
+        let live = self.live.fetch_add(size, Relaxed) + size;
+        self.peak.fetch_max(live, Relaxed);
+    }
+
+    fn freed(&self, size: usize) {
+        self.live.fetch_sub(size, Relaxed);
+    }
+
+    /// Runs `f`, counting its heap use.
+    ///
+    /// Counts are process wide, so other threads allocating meanwhile add to them.
+    pub fn measure<T>(&self, f: impl FnOnce() -> T) -> (T, Allocs) {
+        let (allocs, bytes) = (self.allocs.load(Relaxed), self.bytes.load(Relaxed));
+        let live = self.live.load(Relaxed);
+        self.peak.store(live, Relaxed);
+        let result = f();
+        let allocs = Allocs {
+            count: self.allocs.load(Relaxed) - allocs,
+            bytes: self.bytes.load(Relaxed) - bytes,
+            peak: self.peak.load(Relaxed).saturating_sub(live),
+        };
+        (result, allocs)
+    }
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            self.allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            self.allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        self.freed(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new.is_null() {
+            self.freed(layout.size());
+            self.allocated(new_size);
+        }
+        new
+    }
+}
+
+#[cfg(feature = "count-alloc")]
+#[global_allocator]
+static GLOBAL: Counting = Counting::new();
+
+/// Runs `f`, with its heap use when built with the `count-alloc` feature.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<Allocs>) {
+    #[cfg(feature = "count-alloc")]
+    {
+        let (result, allocs) = GLOBAL.measure(f);
+        (result, Some(allocs))
+    }
+    #[cfg(not(feature = "count-alloc"))]
+    {
+        (f(), None)
+    }
+}
+
+struct Bytes(usize);
+
+impl fmt::Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+        if self.0 < 1024 {
+            return write!(f, "{} B", self.0);
+        }
+        let mut size = self.0 as f64 / 1024.0;
+        let mut unit = 0;
+        while size >= 1024.0 && unit + 1 < UNITS.len() {
+            size /= 1024.0;
+            unit += 1;
+        }
+        write!(f, "{size:.1} {}", UNITS[unit])
+    }
+}
+
+impl fmt::Display for Allocs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} allocs, {} allocated, {} peak",
+            self.count,
+            Bytes(self.bytes),
+            Bytes(self.peak)
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_counting() {
+        let counting = Counting::new();
+        let small = Layout::from_size_align(100, 8).unwrap();
+        let large = Layout::from_size_align(4096, 8).unwrap();
+        let kept = unsafe { counting.alloc(small) };
+
+        let (_, allocs) = counting.measure(|| unsafe {
+            let a = counting.alloc(large);
+            let b = counting.alloc_zeroed(small);
+            counting.dealloc(a, large);
+            let b = counting.realloc(b, small, 300);
+            counting.dealloc(b, Layout::from_size_align(300, 8).unwrap());
+        });
+        unsafe { counting.dealloc(kept, small) };
+
+        assert_eq!(
+            allocs,
+            Allocs {
+                count: 3,
+                bytes: 4096 + 100 + 300,
+                peak: 4096 + 100,
+            }
+        );
+        assert_eq!(counting.live.load(Relaxed), 0);
+    }
+
+    #[test]
+    fn test_display() {
+        let allocs = Allocs {
+            count: 12,
+            bytes: 3 * 1024 * 1024 + 512 * 1024,
+            peak: 1000,
+        };
+        assert_eq!(
+            allocs.to_string(),
+            "12 allocs, 3.5 MiB allocated, 1000 B peak"
+        );
+    }
+}
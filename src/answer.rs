@@ -0,0 +1,199 @@
+use std::{
+    borrow::Cow,
+    convert::Infallible,
+    fmt,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+/// A puzzle answer.
+///
+/// Integers compare equal regardless of how they are stored, so
+/// `Answer::from("19219508902") == Answer::from(19219508902u64)`.
+/// The [`Display`](fmt::Display) form parses back into an equal answer.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Int(i128),
+    /// Integer outside of `i128`, kept as decimal digits without leading zeroes.
+    BigInt {
+        negative: bool,
+        digits: String,
+    },
+    Text(String),
+}
+
+fn is_integer(s: &str) -> bool {
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+impl Answer {
+    fn big(negative: bool, digits: &str) -> Self {
+        let digits = digits.trim_start_matches('0');
+        if digits.is_empty() {
+            return Answer::Int(0);
+        }
+        Answer::BigInt {
+            negative,
+            digits: digits.to_string(),
+        }
+    }
+
+    /// Normalized text used for comparison and hashing.
+    fn canonical(&self) -> Cow<'_, str> {
+        match self {
+            Answer::Int(value) => Cow::Owned(value.to_string()),
+            Answer::BigInt { .. } => Cow::Owned(self.to_string()),
+            Answer::Text(text) if is_integer(text) => {
+                Cow::Owned(Answer::from(text.as_str()).to_string())
+            }
+            Answer::Text(text) => Cow::Borrowed(text),
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        self.canonical() == other.canonical()
+    }
+}
+
+impl Eq for Answer {}
+
+impl Hash for Answer {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.canonical().hash(state)
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(value) => write!(f, "{value}"),
+            Answer::BigInt { negative, digits } => {
+                if *negative {
+                    write!(f, "-")?;
+                }
+                write!(f, "{digits}")
+            }
+            Answer::Text(text) => write!(f, "{text}"),
+        }
+    }
+}
+
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    /// Integers become [`Answer::Int`] or [`Answer::BigInt`], anything else is
+    /// kept as trimmed [`Answer::Text`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if !is_integer(s) {
+            return Ok(Answer::Text(s.to_string()));
+        }
+        Ok(match s.parse::<i128>() {
+            Ok(value) => Answer::Int(value),
+            Err(_) => match s.strip_prefix('-') {
+                Some(digits) => Answer::big(true, digits),
+                None => Answer::big(false, s),
+            },
+        })
+    }
+}
+
+macro_rules! from_int {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(value: $t) -> Self {
+                Answer::Int(value.into())
+            }
+        })*
+    };
+}
+
+from_int!(u8, u16, u32, u64, i8, i16, i32, i64, i128);
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::Int(value as i128)
+    }
+}
+
+impl From<isize> for Answer {
+    fn from(value: isize) -> Self {
+        Answer::Int(value as i128)
+    }
+}
+
+impl From<u128> for Answer {
+    fn from(value: u128) -> Self {
+        match i128::try_from(value) {
+            Ok(value) => Answer::Int(value),
+            Err(_) => Answer::big(false, &value.to_string()),
+        }
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        let Ok(answer) = value.parse();
+        answer
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        value.as_str().into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::*;
+
+    #[test]
+    fn test_mixed_representations() {
+        assert_eq!(Answer::from("19219508902"), Answer::from(19219508902u64));
+        assert_eq!(Answer::from(22517595i64), Answer::from(22517595usize));
+        assert_eq!(Answer::from("-5"), Answer::from(-5i32));
+        assert_ne!(Answer::from("abc"), Answer::from(1u8));
+    }
+
+    #[test]
+    fn test_big() {
+        let big = Answer::from(u128::MAX);
+        assert!(matches!(big, Answer::BigInt { .. }));
+        assert_eq!(big, Answer::from(u128::MAX.to_string()));
+        assert_eq!(
+            Answer::from("-1000000000000000000000000000000000000000"),
+            Answer::BigInt {
+                negative: true,
+                digits: "1000000000000000000000000000000000000000".to_string()
+            }
+        );
+    }
+
+    #[rstest]
+    #[case("984")]
+    #[case("-42")]
+    #[case("0")]
+    #[case("340282366920938463463374607431768211455")]
+    #[case("-99999999999999999999999999999999999999999")]
+    #[case("EFHLPRSL")]
+    #[case("1,2,3")]
+    fn test_roundtrip(#[case] text: &str) {
+        let answer: Answer = text.parse().unwrap();
+        assert_eq!(answer.to_string(), text);
+        assert_eq!(answer.to_string().parse::<Answer>().unwrap(), answer);
+    }
+
+    #[rstest]
+    #[case(" 17\n", "17")]
+    #[case("007", "7")]
+    #[case("-0", "0")]
+    #[case("00000000000000000000000000000000000000000042", "42")]
+    fn test_normalize(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(text.parse::<Answer>().unwrap().to_string(), expected);
+    }
+}
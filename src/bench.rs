@@ -0,0 +1,467 @@
+use std::{
+    fmt,
+    hint::black_box,
+    io,
+    path::Path,
+    process::Command,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    answer::Answer,
+    error::{Error, Result, parse_at},
+    input,
+};
+
+/// Benchmark history, relative to the crate root.
+pub const HISTORY: &str = "bench-history.txt";
+
+/// How long and how often a part is run.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// Time spent running the part before measuring, at least one run.
+    pub warmup: Duration,
+    /// Number of measured runs.
+    pub runs: usize,
+    /// Measuring stops early once this much time is spent, after at least one run.
+    pub budget: Duration,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            warmup: Duration::from_secs(1),
+            runs: 100,
+            budget: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Spread of the durations of one phase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// Stats of `samples`, `None` when there are none.
+    pub fn new(mut samples: Vec<Duration>) -> Option<Self> {
+        samples.sort();
+        // nearest rank: the smallest sample with at least `percent` of them at or below it
+        let rank = |percent: usize| samples[(samples.len() * percent).div_ceil(100).max(1) - 1];
+        Some(Self {
+            min: *samples.first()?,
+            median: rank(50),
+            p95: rank(95),
+            max: *samples.last()?,
+        })
+    }
+}
+
+/// Timings of the parse and solve phases of one part.
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub runs: usize,
+    pub parse: Stats,
+    pub solve: Stats,
+    pub answer: Answer,
+}
+
+/// Answer of a single run, with the time each phase took.
+#[derive(Debug, Clone)]
+pub struct Timed {
+    pub answer: Answer,
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+/// Runs `parse` then `solve` once, `None` when `solve` has no answer.
+pub fn time<P>(
+    parse: impl FnOnce() -> Result<P>,
+    solve: impl FnOnce(&P) -> Result<Option<Answer>>,
+) -> Result<Option<Timed>> {
+    let start = Instant::now();
+    let input = parse()?;
+    let parsed = start.elapsed();
+    let start = Instant::now();
+    let answer = solve(&input)?;
+    let solved = start.elapsed();
+    Ok(answer.map(|answer| Timed {
+        answer,
+        parse: parsed,
+        solve: solved,
+    }))
+}
+
+/// Runs `parse` then `solve` as `options` say, timing each phase on its own.
+///
+/// `None` when `solve` has no answer, errors end the benchmark.
+pub fn measure<P>(
+    options: &Options,
+    parse: impl Fn() -> Result<P>,
+    solve: impl Fn(&P) -> Result<Option<Answer>>,
+) -> Result<Option<Report>> {
+    let Some(answer) = solve(&parse()?)? else {
+        return Ok(None);
+    };
+    let start = Instant::now();
+    while start.elapsed() < options.warmup {
+        black_box(solve(&parse()?)?);
+    }
+
+    let (mut parses, mut solves) = (vec![], vec![]);
+    let start = Instant::now();
+    while parses.len() < options.runs.max(1)
+        && (parses.is_empty() || start.elapsed() < options.budget)
+    {
+        let phase = Instant::now();
+        let input = black_box(parse()?);
+        parses.push(phase.elapsed());
+        let phase = Instant::now();
+        black_box(solve(&input)?);
+        solves.push(phase.elapsed());
+    }
+    Ok(Some(Report {
+        runs: parses.len(),
+        parse: Stats::new(parses).expect("at least one run"),
+        solve: Stats::new(solves).expect("at least one run"),
+        answer,
+    }))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Parse,
+    Solve,
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::Parse => write!(f, "parse"),
+            Phase::Solve => write!(f, "solve"),
+        }
+    }
+}
+
+/// Stats of one phase of a part, as saved in the history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    /// Commit the code was at, with `-dirty` when it had uncommitted changes.
+    pub commit: String,
+    pub profile: String,
+    pub day: u8,
+    pub part: u8,
+    pub phase: Phase,
+    pub runs: usize,
+    pub stats: Stats,
+}
+
+impl Record {
+    /// The parse and solve records of `report`.
+    pub fn from_report(
+        commit: &str,
+        profile: &str,
+        day: u8,
+        part: u8,
+        report: &Report,
+    ) -> [Self; 2] {
+        [(Phase::Parse, report.parse), (Phase::Solve, report.solve)].map(|(phase, stats)| Self {
+            commit: commit.to_string(),
+            profile: profile.to_string(),
+            day,
+            part,
+            phase,
+            runs: report.runs,
+            stats,
+        })
+    }
+
+    /// Latest record of the same phase of the same part at `commit` in `history`.
+    pub fn baseline<'a>(&self, history: &'a [Record], commit: &str) -> Option<&'a Record> {
+        history.iter().rev().find(|record| {
+            record.commit == commit
+                && record.profile == self.profile
+                && (record.day, record.part, record.phase) == (self.day, self.part, self.phase)
+        })
+    }
+
+    /// Change of the median against `baseline`, in percent.
+    pub fn change(&self, baseline: &Record) -> f64 {
+        let (now, then) = (self.stats.median, baseline.stats.median);
+        (now.as_secs_f64() / then.as_secs_f64().max(f64::MIN_POSITIVE) - 1.0) * 100.0
+    }
+}
+
+/// Parses `<commit> <profile> <day> <part> <phase> <runs> <min> <median> <p95> <max>`
+/// lines, with durations in nanoseconds.
+pub fn parse_history(text: &str) -> Result<Vec<Record>> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [
+                commit,
+                profile,
+                day,
+                part,
+                phase,
+                runs,
+                min,
+                median,
+                p95,
+                max,
+            ] = fields[..]
+            else {
+                return Err(Error::at(text, line, "expected a benchmark record"));
+            };
+            let phase = match phase {
+                "parse" => Phase::Parse,
+                "solve" => Phase::Solve,
+                _ => return Err(Error::at(text, phase, format!("unknown phase `{phase}`"))),
+            };
+            let nanos = |token| parse_at(text, token, "nanoseconds").map(Duration::from_nanos);
+            Ok(Record {
+                commit: commit.to_string(),
+                profile: profile.to_string(),
+                day: parse_at(text, day, "a day")?,
+                part: parse_at(text, part, "a part")?,
+                phase,
+                runs: parse_at(text, runs, "a number of runs")?,
+                stats: Stats {
+                    min: nanos(min)?,
+                    median: nanos(median)?,
+                    p95: nanos(p95)?,
+                    max: nanos(max)?,
+                },
+            })
+        })
+        .collect()
+}
+
+/// Reads the history at `path`, an empty one if there is none yet.
+pub fn load_history(path: &Path) -> Result<Vec<Record>> {
+    match input::read(path)? {
+        Some(text) => parse_history(&text),
+        None => Ok(vec![]),
+    }
+}
+
+/// Adds `record` to the history at `path`.
+pub fn append_history(path: &Path, record: &Record) -> Result<()> {
+    let Record {
+        commit,
+        profile,
+        day,
+        part,
+        phase,
+        runs,
+        stats,
+    } = record;
+    let [min, median, p95, max] =
+        [stats.min, stats.median, stats.p95, stats.max].map(|d| d.as_nanos());
+    input::append_line(
+        path,
+        &format!("{commit} {profile} {day} {part} {phase} {runs} {min} {median} {p95} {max}"),
+    )
+}
+
+fn git(args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(input::crate_root())
+        .output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::Io(io::Error::other(format!(
+            "git {}: {}",
+            args.join(" "),
+            stderr.trim()
+        ))));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Full hash of the git revision `rev`.
+pub fn commit(rev: &str) -> Result<String> {
+    git(&[
+        "rev-parse",
+        "--verify",
+        "--quiet",
+        &format!("{rev}^{{commit}}"),
+    ])
+    .map_err(|_| Error::Io(io::Error::other(format!("unknown revision `{rev}`"))))
+}
+
+/// Commit the working tree is at, `-dirty` when tracked files have changes.
+pub fn current_commit() -> Result<String> {
+    let head = commit("HEAD")?;
+    let changes = git(&["status", "--porcelain", "--untracked-files=no"])?;
+    Ok(if changes.is_empty() {
+        head
+    } else {
+        format!("{head}-dirty")
+    })
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Stats {
+            min,
+            median,
+            p95,
+            max,
+        } = self;
+        write!(f, "{min:>10.2?} {median:>10.2?} {p95:>10.2?} {max:>10.2?}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::{input::Scratch, solution};
+
+    #[test]
+    fn test_stats() {
+        let ms = Duration::from_millis;
+        assert_eq!(Stats::new(vec![]), None);
+        assert_eq!(
+            Stats::new(vec![ms(3)]).unwrap(),
+            Stats {
+                min: ms(3),
+                median: ms(3),
+                p95: ms(3),
+                max: ms(3),
+            }
+        );
+        let stats = Stats::new((1..=20).rev().map(ms).collect()).unwrap();
+        assert_eq!(
+            stats,
+            Stats {
+                min: ms(1),
+                median: ms(10),
+                p95: ms(19),
+                max: ms(20),
+            }
+        );
+    }
+
+    #[test]
+    fn test_measure() {
+        let options = Options {
+            warmup: Duration::ZERO,
+            runs: 5,
+            budget: Duration::from_secs(10),
+        };
+        let (parses, solves) = (Cell::new(0), Cell::new(0));
+        let report = measure(
+            &options,
+            || {
+                parses.set(parses.get() + 1);
+                Ok(parses.get())
+            },
+            |_| {
+                solves.set(solves.get() + 1);
+                Ok(Some(42.into()))
+            },
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(report.runs, 5);
+        assert_eq!(report.answer, 42.into());
+        // one run for the answer, then the measured ones
+        assert_eq!((parses.get(), solves.get()), (6, 6));
+        assert!(report.parse.min <= report.parse.median && report.parse.p95 <= report.parse.max);
+
+        let budget = Options {
+            budget: Duration::ZERO,
+            ..options
+        };
+        let report = measure(&budget, || Ok(()), |_| Ok(Some(1.into()))).unwrap();
+        assert_eq!(report.unwrap().runs, 1);
+        assert!(measure(&budget, || Ok(()), |_| Ok(None)).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_history() {
+        let ms = Duration::from_millis;
+        let stats = |median| Stats {
+            min: ms(1),
+            median: ms(median),
+            p95: ms(20),
+            max: ms(30),
+        };
+        let report = Report {
+            runs: 7,
+            parse: stats(2),
+            solve: stats(10),
+            answer: 1.into(),
+        };
+        let dir = Scratch::new("bench");
+        let path = dir.join(HISTORY);
+        let missing = load_history(&path).unwrap();
+        for record in Record::from_report("abc", "default", 10, 2, &report)
+            .into_iter()
+            .chain(Record::from_report("def-dirty", "default", 10, 2, &report))
+        {
+            append_history(&path, &record).unwrap();
+        }
+        let text = std::fs::read_to_string(&path).unwrap();
+        let history = load_history(&path).unwrap();
+
+        assert!(missing.is_empty());
+        assert_eq!(
+            text.lines().next().unwrap(),
+            "abc default 10 2 parse 7 1000000 2000000 20000000 30000000"
+        );
+        assert_eq!(history.len(), 4);
+        assert_eq!(history[1].phase, Phase::Solve);
+        assert_eq!(history[3].commit, "def-dirty");
+
+        let slower = Record {
+            stats: stats(12),
+            ..history[3].clone()
+        };
+        let baseline = slower.baseline(&history, "abc").unwrap();
+        assert_eq!(baseline, &history[1]);
+        assert!((slower.change(baseline) - 20.0).abs() < 1e-9);
+        assert_eq!(slower.baseline(&history, "xyz"), None);
+        let other = Record {
+            profile: "example".to_string(),
+            ..slower
+        };
+        assert_eq!(other.baseline(&history, "abc"), None);
+    }
+
+    #[test]
+    fn test_parse_history_errors() {
+        let err = parse_history("abc default 1 1 parse 5 1 2 3").unwrap_err();
+        assert_eq!(err.to_string(), "1:1: expected a benchmark record");
+        let err = parse_history("abc default 1 1 run 5 1 2 3 4").unwrap_err();
+        assert_eq!(err.to_string(), "1:17: unknown phase `run`");
+        let err = parse_history("abc default 1 1 solve 5 1 2 x 4").unwrap_err();
+        assert_eq!(err.to_string(), "1:29: expected nanoseconds, found `x`");
+    }
+
+    #[test]
+    fn test_bench_day() {
+        let input = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82";
+        let options = Options {
+            warmup: Duration::ZERO,
+            runs: 3,
+            budget: Duration::from_secs(10),
+        };
+        let day = solution::find(1).unwrap();
+        assert_eq!(
+            day.bench(2, input, &options).unwrap().unwrap().answer,
+            6.into()
+        );
+        assert!(day.bench(3, input, &options).unwrap().is_none());
+        assert!(day.bench(1, "X", &options).is_err());
+    }
+}
@@ -1,36 +1,45 @@
-use std::{num::ParseIntError, str::FromStr};
+use std::{borrow::Borrow, io::BufRead};
 
-enum Rotation {
+use itertools::process_results;
+
+use crate::{
+    answer::Answer,
+    differential::Check,
+    error::{Error, Result, parse_at},
+    generator::Rng,
+    solution::Solution,
+    stream,
+};
+
+pub enum Rotation {
     Left(i32),
     Rigth(i32),
 }
 
-impl FromStr for Rotation {
-    type Err = ParseIntError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Some(value) = s.strip_prefix('L') {
-            Ok(Self::Left(value.parse()?))
-        } else if let Some(value) = s.strip_prefix('R') {
-            Ok(Self::Rigth(value.parse()?))
-        } else {
-            panic!("Not a valid rotation!")
-        }
+fn parse_rotation(input: &str, line: &str) -> Result<Rotation> {
+    if let Some(value) = line.strip_prefix('L') {
+        Ok(Rotation::Left(parse_at(input, value, "a number")?))
+    } else if let Some(value) = line.strip_prefix('R') {
+        Ok(Rotation::Rigth(parse_at(input, value, "a number")?))
+    } else {
+        Err(Error::at(input, line, "expected `L` or `R`"))
     }
 }
 
-fn parse_input(input: &str) -> Vec<Rotation> {
+fn parse_input(input: &str) -> Result<Vec<Rotation>> {
     input
         .lines()
-        .map(|line| line.parse::<Rotation>().expect("Valid rotation"))
+        .map(|line| parse_rotation(input, line))
         .collect()
 }
 
-pub fn count_rotations_point_at_zeroes(input: &str) -> usize {
+pub fn count_rotations_point_at_zeroes(
+    rotations: impl IntoIterator<Item = impl Borrow<Rotation>>,
+) -> usize {
     let mut pos: i32 = 50;
     let mut zeroes: usize = 0;
-    for turn in parse_input(input) {
-        pos = match turn {
+    for turn in rotations {
+        pos = match turn.borrow() {
             Rotation::Left(val) => pos - val,
             Rotation::Rigth(val) => pos + val,
         } % 100;
@@ -41,11 +50,13 @@ pub fn count_rotations_point_at_zeroes(input: &str) -> usize {
     zeroes
 }
 
-pub fn count_rotations_click_at_zeroes(input: &str) -> usize {
+pub fn count_rotations_click_at_zeroes(
+    rotations: impl IntoIterator<Item = impl Borrow<Rotation>>,
+) -> usize {
     let mut pos: usize = 50;
     let mut zeroes: usize = 0;
-    for turn in parse_input(input) {
-        let mut new_pos = match turn {
+    for turn in rotations {
+        let mut new_pos = match turn.borrow() {
             Rotation::Left(val) => pos as i32 - val,
             Rotation::Rigth(val) => pos as i32 + val,
         };
@@ -67,9 +78,77 @@ pub fn count_rotations_click_at_zeroes(input: &str) -> usize {
     zeroes
 }
 
+/// Part 2 the slow way, turning the dial one click at a time.
+fn count_clicks_one_by_one(rotations: &[Rotation]) -> usize {
+    let mut pos: i32 = 50;
+    let mut zeroes = 0;
+    for turn in rotations {
+        let (step, clicks) = match *turn {
+            Rotation::Left(val) => (-1, val),
+            Rotation::Rigth(val) => (1, val),
+        };
+        for _ in 0..clicks {
+            pos = (pos + step).rem_euclid(100);
+            if pos == 0 {
+                zeroes += 1;
+            }
+        }
+    }
+    zeroes
+}
+
+const CHECKS: &[Check] = &[Check {
+    name: "day01::part2",
+    day: 1,
+    size: 200,
+    reference: |input| Ok(count_clicks_one_by_one(&parse_input(input)?).into()),
+    optimized: |input| Ok(count_rotations_click_at_zeroes(parse_input(input)?).into()),
+}];
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+    type Input<'a> = Vec<Rotation>;
+    type Config = ();
+    const CHECKS: &'static [Check] = CHECKS;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse_input(input)
+    }
+
+    fn part1(rotations: &Self::Input<'_>, _: &()) -> Result<Answer> {
+        Ok(count_rotations_point_at_zeroes(rotations).into())
+    }
+
+    fn part2(rotations: &Self::Input<'_>, _: &()) -> Result<Option<Answer>> {
+        Ok(Some(count_rotations_click_at_zeroes(rotations).into()))
+    }
+
+    fn stream(part: u8, reader: &mut dyn BufRead, _: &()) -> Result<Option<Answer>> {
+        let rotations =
+            stream::lines(reader).map(|line| line?.parse(|line| parse_rotation(line, line)));
+        let count = match part {
+            1 => process_results(rotations, |r| count_rotations_point_at_zeroes(r))?,
+            2 => process_results(rotations, |r| count_rotations_click_at_zeroes(r))?,
+            _ => return Ok(None),
+        };
+        Ok(Some(count.into()))
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let rotations = (0..size).map(|_| {
+            let direction = if rng.chance(1, 2) { 'L' } else { 'R' };
+            format!("{direction}{}\n", rng.between(1, 999))
+        });
+        Some(rotations.collect())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::verify::assert_answers;
 
     #[test]
     fn test_part1() {
@@ -84,11 +163,13 @@ L99
 R14
 L82
 ";
-        assert_eq!(count_rotations_point_at_zeroes(input), 3);
         assert_eq!(
-            count_rotations_point_at_zeroes(include_str!("../input/day01.txt")),
-            984
+            count_rotations_point_at_zeroes(parse_input(input).unwrap()),
+            3
         );
+        assert_answers(1, 1, |input| {
+            count_rotations_point_at_zeroes(parse_input(input).unwrap())
+        });
     }
     #[test]
     fn test_part2() {
@@ -103,10 +184,36 @@ L99
 R14
 L82
 ";
-        assert_eq!(count_rotations_click_at_zeroes(input), 6);
         assert_eq!(
-            count_rotations_click_at_zeroes(include_str!("../input/day01.txt")),
-            5657
+            count_rotations_click_at_zeroes(parse_input(input).unwrap()),
+            6
         );
+        assert_answers(1, 2, |input| {
+            count_rotations_click_at_zeroes(parse_input(input).unwrap())
+        });
+        assert_eq!(count_clicks_one_by_one(&parse_input(input).unwrap()), 6);
+    }
+
+    #[test]
+    fn test_stream() {
+        let input = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+        assert_eq!(
+            Day01::stream(1, &mut input.as_bytes(), &()).unwrap(),
+            Some(3.into())
+        );
+        assert_eq!(
+            Day01::stream(2, &mut input.as_bytes(), &()).unwrap(),
+            Some(6.into())
+        );
+        let err = Day01::stream(1, &mut "L68\nR3o".as_bytes(), &()).unwrap_err();
+        assert_eq!(err.to_string(), "2:2: expected a number, found `3o`");
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = parse_input("L68\nL30\nX48").err().unwrap();
+        assert_eq!(err.to_string(), "3:1: expected `L` or `R`");
+        let err = parse_input("L68\nR3o").err().unwrap();
+        assert_eq!(err.to_string(), "2:2: expected a number, found `3o`");
     }
 }
@@ -1,5 +1,20 @@
-fn is_invalid(n: &usize) -> bool {
-    let digits = n.ilog10() + 1;
+use std::{borrow::Borrow, collections::HashSet, io::BufRead};
+
+use itertools::process_results;
+
+use crate::{
+    answer::Answer,
+    differential::Check,
+    error::{Error, Result, parse_at},
+    generator::Rng,
+    minimize::Shape,
+    parallel,
+    solution::Solution,
+    stream,
+};
+
+pub fn is_invalid(n: &usize) -> bool {
+    let digits = n.checked_ilog10().map_or(1, |log| log + 1);
     if !digits.is_multiple_of(2) {
         return false;
     }
@@ -7,7 +22,7 @@ fn is_invalid(n: &usize) -> bool {
     let upper = n / half;
     let lower = n % half;
     if upper == lower {
-        dbg!(n);
+        tracing::info!(n);
         true
     } else {
         false
@@ -15,8 +30,8 @@ fn is_invalid(n: &usize) -> bool {
 }
 
 #[tracing::instrument]
-fn is_really_invalid(n: &usize) -> bool {
-    let digits = n.ilog10() + 1;
+pub fn is_really_invalid(n: &usize) -> bool {
+    let digits = n.checked_ilog10().map_or(1, |log| log + 1);
 
     'outer: for len in 1..=(digits / 2) {
         if !digits.is_multiple_of(len) {
@@ -37,30 +52,157 @@ fn is_really_invalid(n: &usize) -> bool {
     false
 }
 
-fn parse_range(range: &str) -> (usize, usize) {
-    let (first, second) = range.split_once('-').unwrap();
-    (first.parse().unwrap(), second.trim().parse().unwrap())
+fn parse_range(input: &str, range: &str) -> Result<(usize, usize)> {
+    let range = range.trim();
+    let (first, second) = range
+        .split_once('-')
+        .ok_or_else(|| Error::at(input, range, "expected `<first>-<last>`"))?;
+    Ok((
+        parse_at(input, first, "a number")?,
+        parse_at(input, second, "a number")?,
+    ))
 }
 
-fn process(input: &str, predicate: fn(&usize) -> bool) -> String {
-    let result: usize = input
+fn parse(input: &str) -> Result<Vec<(usize, usize)>> {
+    input
         .split(',')
-        .map(parse_range)
-        .map(|(a, b)| (a..=b).filter(predicate).sum::<usize>())
+        .map(|range| parse_range(input, range))
+        .collect()
+}
+
+/// Sum of the IDs from `first` to `last` that match `predicate`.
+fn sum_range((first, last): (usize, usize), predicate: fn(&usize) -> bool) -> usize {
+    (first..=last).filter(predicate).sum()
+}
+
+pub fn process(
+    ranges: impl IntoIterator<Item = impl Borrow<(usize, usize)>>,
+    predicate: fn(&usize) -> bool,
+) -> String {
+    let result: usize = ranges
+        .into_iter()
+        .map(|range| sum_range(*range.borrow(), predicate))
         .sum();
     result.to_string()
 }
 
+/// Sum of the IDs from `first` to `last` made of a block of digits repeated exactly
+/// twice, or at least twice, built from the blocks instead of testing every ID.
+pub fn sum_repeated((first, last): (usize, usize), exactly_twice: bool) -> usize {
+    let digits = |n: usize| n.checked_ilog10().map_or(1, |log| log + 1);
+    let mut ids = HashSet::new();
+    for len in digits(first)..=digits(last) {
+        for block in (1..=len / 2).filter(|block| len.is_multiple_of(*block)) {
+            if exactly_twice && len != 2 * block {
+                continue;
+            }
+            // 1010...1 with `len / block` ones, times the block gives the ID
+            let repeat = (0..len / block).fold(0, |acc, _| acc * 10_usize.pow(block) + 1);
+            let low = 10_usize.pow(block - 1).max(first.div_ceil(repeat));
+            let high = (10_usize.pow(block) - 1).min(last / repeat);
+            ids.extend((low..=high).map(|block| block * repeat));
+        }
+    }
+    ids.into_iter().sum()
+}
+
+/// [`process`] by [`sum_repeated`], with the ranges split between `jobs` workers.
+pub fn process_fast(ranges: &[(usize, usize)], exactly_twice: bool, jobs: usize) -> String {
+    let sums = parallel::map(ranges, jobs, |_, &range| sum_repeated(range, exactly_twice));
+    sums.into_iter().sum::<usize>().to_string()
+}
+
+const CHECKS: &[Check] = &[
+    Check {
+        name: "day02::part1",
+        day: 2,
+        size: 30,
+        reference: |input| Ok(process(parse(input)?, is_invalid).into()),
+        optimized: |input| Ok(process_fast(&parse(input)?, true, 1).into()),
+    },
+    Check {
+        name: "day02::part2",
+        day: 2,
+        size: 30,
+        reference: |input| Ok(process(parse(input)?, is_really_invalid).into()),
+        optimized: |input| Ok(process_fast(&parse(input)?, false, 1).into()),
+    },
+];
+
+pub struct Config {
+    /// Workers the ranges are split between.
+    pub jobs: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self { jobs: 1 }
+    }
+}
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+    type Input<'a> = Vec<(usize, usize)>;
+    type Config = Config;
+    const SHAPE: Shape = Shape::Records(',');
+    const CHECKS: &'static [Check] = CHECKS;
+
+    fn with_jobs(_: Config, jobs: usize) -> Config {
+        Config { jobs }
+    }
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse(input)
+    }
+
+    fn part1(ranges: &Self::Input<'_>, config: &Config) -> Result<Answer> {
+        Ok(process_fast(ranges, true, config.jobs).into())
+    }
+
+    fn part2(ranges: &Self::Input<'_>, config: &Config) -> Result<Option<Answer>> {
+        Ok(Some(process_fast(ranges, false, config.jobs).into()))
+    }
+
+    fn stream(part: u8, reader: &mut dyn BufRead, _: &Config) -> Result<Option<Answer>> {
+        let exactly_twice = match part {
+            1 => true,
+            2 => false,
+            _ => return Ok(None),
+        };
+        let ranges = stream::records(reader, b',')
+            .map(|range| range?.parse(|range| parse_range(range, range)));
+        let sum: usize = process_results(ranges, |ranges| {
+            ranges.map(|range| sum_repeated(range, exactly_twice)).sum()
+        })?;
+        Ok(Some(sum.into()))
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let ranges: Vec<_> = (0..size.max(1))
+            .map(|_| {
+                let digits = rng.between(1, 10) as u32;
+                let first = rng.between(10_u64.pow(digits - 1), 10_u64.pow(digits) - 1);
+                format!("{first}-{}", first + rng.between(0, 5000))
+            })
+            .collect();
+        Some(ranges.join(",") + "\n")
+    }
+}
+
 #[cfg(test)]
 mod test {
 
     use super::*;
+    use crate::verify::assert_answers;
     use rstest::*;
 
     #[rstest]
     #[case(11, true)]
     #[case(101, false)]
     #[case(1, false)]
+    #[case(0, false)]
     #[case(123123, true)]
     fn test_ivalid(#[case] n: usize, #[case] expected: bool) {
         assert_eq!(is_invalid(&n), expected)
@@ -71,6 +213,7 @@ mod test {
     #[case(101, false)]
     #[case(111, true)]
     #[case(1, false)]
+    #[case(0, false)]
     #[case(123123, true)]
     #[case(1188511885, true)]
     fn test_really_ivalid(#[case] n: usize, #[case] expected: bool) {
@@ -80,15 +223,55 @@ mod test {
     #[test_log::test]
     fn test_solution() {
         let input = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124";
-        assert_eq!(process(input, is_invalid), "1227775554");
+        let input = parse(input).unwrap();
+        assert_eq!(process(&input, is_invalid), "1227775554");
+        assert_answers(2, 1, |input| process(parse(input).unwrap(), is_invalid));
+        assert_eq!(process(&input, is_really_invalid), "4174379265");
+        assert_answers(2, 2, |input| {
+            process(parse(input).unwrap(), is_really_invalid)
+        });
+    }
+
+    #[test]
+    fn test_fast() {
+        let input = parse("11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124").unwrap();
+        assert_eq!(process_fast(&input, true, 1), "1227775554");
+        assert_eq!(process_fast(&input, false, 1), "4174379265");
+        // 111 is one block three times, and 1111 both ways but counted once
         assert_eq!(
-            process(include_str!("../input/day02.txt"), is_invalid),
-            "19219508902"
+            sum_repeated((100, 1111), false),
+            111 + 222 + 333 + 444 + 555 + 666 + 777 + 888 + 999 + 1010 + 1111
         );
-        assert_eq!(process(input, is_really_invalid), "4174379265");
+        assert_answers(2, 1, |input| process_fast(&parse(input).unwrap(), true, 1));
+        assert_answers(2, 2, |input| process_fast(&parse(input).unwrap(), false, 1));
+    }
+
+    #[test]
+    fn test_parallel() {
+        let input = parse("11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124").unwrap();
+        for jobs in [1, 2, 4, 16] {
+            assert_eq!(process_fast(&input, true, jobs), "1227775554");
+            assert_eq!(process_fast(&input, false, jobs), "4174379265");
+        }
+    }
+
+    #[test]
+    fn test_stream() {
+        let input = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124\n";
+        let stream =
+            |part, input: &str| Day02::stream(part, &mut input.as_bytes(), &Config::default());
+        assert_eq!(stream(1, input).unwrap(), Some(1227775554.into()));
+        assert_eq!(stream(2, input).unwrap(), Some(4174379265_u64.into()));
         assert_eq!(
-            process(include_str!("../input/day02.txt"), is_really_invalid),
-            "27180728081"
+            stream(1, "11-22,\n95-1x5").unwrap_err().to_string(),
+            "2:4: expected a number, found `1x5`"
         );
     }
+
+    #[rstest]
+    #[case("11-22,95", "1:7: expected `<first>-<last>`")]
+    #[case("11-22,\n95-1x5", "2:4: expected a number, found `1x5`")]
+    fn test_parse_error(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(parse(input).unwrap_err().to_string(), expected);
+    }
 }
@@ -1,5 +1,17 @@
+use std::io::BufRead;
+
+use itertools::process_results;
 use tracing::info;
 
+use crate::{
+    answer::Answer,
+    error::{Error, Result},
+    generator::Rng,
+    parallel,
+    solution::Solution,
+    stream,
+};
+
 #[tracing::instrument(skip(bank, size))]
 fn extract_joltage(bank: &str, size: usize) -> usize {
     fn find_max(line: &str) -> (char, usize) {
@@ -28,17 +40,118 @@ fn extract_joltage(bank: &str, size: usize) -> usize {
     result
 }
 
-fn process(input: &str, size: usize) -> String {
-    input
-        .lines()
-        .map(|v| extract_joltage(v, size))
-        .sum::<usize>()
-        .to_string()
+fn parse_bank<'a>(input: &str, bank: &'a str) -> Result<&'a str> {
+    match bank.find(|c: char| !c.is_ascii_digit()) {
+        Some(pos) => Err(Error::at(input, &bank[pos..], "expected a battery digit")),
+        None => Ok(bank),
+    }
+}
+
+fn parse(input: &str) -> Result<Vec<&str>> {
+    input.lines().map(|bank| parse_bank(input, bank)).collect()
+}
+
+fn joltage(bank: &str, size: usize) -> Result<usize> {
+    if bank.len() < size {
+        return Err(Error::puzzle(format!(
+            "bank `{bank}` has less than {size} batteries"
+        )));
+    }
+    Ok(extract_joltage(bank, size))
+}
+
+pub fn process(banks: impl IntoIterator<Item = impl AsRef<str>>, size: usize) -> Result<String> {
+    Ok(banks
+        .into_iter()
+        .map(|bank| joltage(bank.as_ref(), size))
+        .sum::<Result<usize>>()?
+        .to_string())
+}
+
+/// [`process`] with the banks split between `jobs` workers.
+pub fn process_parallel(banks: &[&str], size: usize, jobs: usize) -> Result<String> {
+    Ok(parallel::map(banks, jobs, |_, bank| joltage(bank, size))
+        .into_iter()
+        .sum::<Result<usize>>()?
+        .to_string())
+}
+
+pub struct Config {
+    /// Batteries turned on per bank in part 1.
+    pub part1_size: usize,
+    /// Batteries turned on per bank in part 2.
+    pub part2_size: usize,
+    /// Workers the banks are split between.
+    pub jobs: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            part1_size: 2,
+            part2_size: 12,
+            jobs: 1,
+        }
+    }
+}
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u8 = 3;
+    type Input<'a> = Vec<&'a str>;
+    type Config = Config;
+
+    fn with_jobs(config: Config, jobs: usize) -> Config {
+        Config { jobs, ..config }
+    }
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse(input)
+    }
+
+    fn part1(banks: &Self::Input<'_>, config: &Config) -> Result<Answer> {
+        Ok(process_parallel(banks, config.part1_size, config.jobs)?.into())
+    }
+
+    fn part2(banks: &Self::Input<'_>, config: &Config) -> Result<Option<Answer>> {
+        Ok(Some(
+            process_parallel(banks, config.part2_size, config.jobs)?.into(),
+        ))
+    }
+
+    fn stream(part: u8, reader: &mut dyn BufRead, config: &Config) -> Result<Option<Answer>> {
+        let size = match part {
+            1 => config.part1_size,
+            2 => config.part2_size,
+            _ => return Ok(None),
+        };
+        let banks = stream::lines(reader).map(|bank| -> Result<_> {
+            let bank = bank?;
+            bank.parse(|text| parse_bank(text, text).map(|_| ()))?;
+            Ok(bank.text)
+        });
+        Ok(Some(
+            process_results(banks, |banks| process(banks, size))??.into(),
+        ))
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let banks = (0..size).map(|_| {
+            let mut bank: String = (0..100)
+                .map(|_| char::from(b'0' + rng.between(1, 9) as u8))
+                .collect();
+            bank.push('\n');
+            bank
+        });
+        Some(banks.collect())
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::verify::assert_answers;
     use rstest::*;
 
     #[rstest]
@@ -58,13 +171,32 @@ mod test {
 811111111111119
 234234234234278
 818181911112111";
-        assert_eq!(process(input, 2), "357");
-        assert_eq!(process(include_str!("../input/day03.txt"), 2), "16854");
+        let input = parse(input).unwrap();
+        assert_eq!(process(&input, 2).unwrap(), "357");
+        assert_answers(3, 1, |input| process(parse(input).unwrap(), 2).unwrap());
 
-        assert_eq!(process(input, 12), "3121910778619");
+        assert_eq!(process(&input, 12).unwrap(), "3121910778619");
+        assert_answers(3, 2, |input| process(parse(input).unwrap(), 12).unwrap());
+    }
+
+    #[test]
+    fn test_stream() {
+        let input = "987654321111111\n811111111111119\n234234234234278\n818181911112111\n";
+        let config = Config::default();
+        let stream = |part, input: &str| Day03::stream(part, &mut input.as_bytes(), &config);
+        assert_eq!(stream(1, input).unwrap(), Some(357.into()));
+        assert_eq!(stream(2, input).unwrap(), Some(3121910778619_u64.into()));
         assert_eq!(
-            process(include_str!("../input/day03.txt"), 12),
-            "167526011932478"
+            stream(1, "987\n81a1").unwrap_err().to_string(),
+            "2:3: expected a battery digit"
         );
+        assert!(stream(1, "987\n8").is_err());
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = parse("987\n81a1").unwrap_err();
+        assert_eq!(err.to_string(), "2:3: expected a battery digit");
+        assert!(process(["987", "8"], 2).is_err());
     }
 }
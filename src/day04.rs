@@ -1,38 +1,31 @@
+use glam::USizeVec2;
+
+use crate::{
+    answer::Answer, error::Result, generator::Rng, grid::Grid, minimize::Shape, solution::Solution,
+};
+
 #[tracing::instrument(ret, skip(input))]
-fn count_neighbours(input: &[Vec<char>], r: &usize, c: &usize) -> usize {
-    (-1..=1)
-        .cartesian_product(-1..=1)
-        .filter(|(i, j)| !(*i == 0 && *j == 0))
-        .filter_map(move |(i, j)| -> Option<()> {
-            if input
-                .get(r.checked_add_signed(i)?)?
-                .get(c.checked_add_signed(j)?)?
-                == &'@'
-            {
-                Some(())
-            } else {
-                None
-            }
-        })
+fn count_neighbours(input: &Grid<char>, pos: USizeVec2) -> usize {
+    input
+        .neighbours8(pos)
+        .filter(|&neighbour| input[neighbour] == '@')
         .count()
 }
-use itertools::Itertools;
-fn process(input: &str) -> usize {
-    let input: Vec<Vec<_>> = input.lines().map(|line| line.chars().collect()).collect();
-    let height = input.len();
-    let width = input[0].len();
-
-    (0..height)
-        .cartesian_product(0..width)
-        .filter(|(r, c)| input[*r][*c] == '@')
-        .filter(|(r, c)| count_neighbours(&input, r, c) < 4)
+
+fn parse(input: &str) -> Result<Grid<char>> {
+    Grid::parse(input, |c| matches!(c, '.' | '@').then_some(c))
+}
+
+pub fn process(input: &Grid<char>) -> usize {
+    input
+        .positions()
+        .filter(|&pos| input[pos] == '@')
+        .filter(|&pos| count_neighbours(input, pos) < 4)
         .count()
 }
 
-fn process2(input: &str) -> usize {
-    let mut input: Vec<Vec<_>> = input.lines().map(|line| line.chars().collect()).collect();
-    let height = input.len();
-    let width = input[0].len();
+pub fn process2(input: &Grid<char>) -> usize {
+    let mut input = input.clone();
 
     let mut total = 0;
     let mut removed = 0;
@@ -42,22 +35,54 @@ fn process2(input: &str) -> usize {
         total += removed;
         removed = 0;
 
-        for r in 0..height {
-            for c in 0..width {
-                if input[r][c] == '@' && count_neighbours(&input, &r, &c) < 4 {
-                    removed += 1;
-                    input[r][c] = 'x';
-                }
+        for pos in input.positions() {
+            if input[pos] == '@' && count_neighbours(&input, pos) < 4 {
+                removed += 1;
+                input[pos] = 'x';
             }
         }
     }
     total
 }
 
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+    type Input<'a> = Grid<char>;
+    type Config = ();
+    const SHAPE: Shape = Shape::Grid;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse(input)
+    }
+
+    fn part1(rolls: &Self::Input<'_>, _: &()) -> Result<Answer> {
+        Ok(process(rolls).into())
+    }
+
+    fn part2(rolls: &Self::Input<'_>, _: &()) -> Result<Option<Answer>> {
+        Ok(Some(process2(rolls).into()))
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let size = size.max(1);
+        let rows = (0..size).map(|_| {
+            let mut row: String = (0..size)
+                .map(|_| if rng.chance(3, 5) { '@' } else { '.' })
+                .collect();
+            row.push('\n');
+            row
+        });
+        Some(rows.collect())
+    }
+}
+
 #[cfg(test)]
 mod test {
 
     use super::*;
+    use crate::verify::assert_answers;
 
     #[test_log::test]
     fn test_solution() {
@@ -72,10 +97,23 @@ mod test {
 .@@@@@@@@.
 @.@.@@@.@.";
 
-        assert_eq!(process(input), 13);
-        assert_eq!(process(include_str!("../input/day04.txt")), 1505);
+        let input = parse(input).unwrap();
+        assert_eq!(process(&input), 13);
+        assert_answers(4, 1, |input| process(&parse(input).unwrap()));
+
+        assert_eq!(process2(&input), 43);
+        assert_answers(4, 2, |input| process2(&parse(input).unwrap()));
+    }
 
-        assert_eq!(process2(input), 43);
-        assert_eq!(process2(include_str!("../input/day04.txt")), 9182);
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            parse("..@\n.#.").unwrap_err().to_string(),
+            "2:2: unexpected `#`"
+        );
+        assert_eq!(
+            parse("..@\n.@").unwrap_err().to_string(),
+            "2:1: expected 3 columns"
+        );
     }
 }
@@ -1,49 +1,77 @@
-use std::ops::RangeInclusive;
+use std::{io::BufRead, ops::RangeInclusive};
 
-use itertools::Itertools;
+use itertools::{Itertools, process_results};
 use tracing::info;
 
-#[tracing::instrument(skip(input))]
-fn process(input: &str) -> usize {
+use crate::{
+    answer::Answer,
+    error::{Error, Result, parse_at},
+    generator::Rng,
+    solution::Solution,
+    stream,
+};
+
+pub struct Inventory {
+    fresh: Vec<RangeInclusive<usize>>,
+    available: Vec<usize>,
+}
+
+fn parse_range(input: &str, line: &str) -> Result<RangeInclusive<usize>> {
+    let (start, end) = line
+        .split_once('-')
+        .ok_or_else(|| Error::at(input, line, "expected `<start>-<end>`"))?;
+    let (start, end) = (
+        parse_at(input, start, "a number")?,
+        parse_at(input, end, "a number")?,
+    );
+    if start > end {
+        return Err(Error::at(
+            input,
+            line,
+            format!("range {start}-{end} ends before it starts"),
+        ));
+    }
+    Ok(RangeInclusive::new(start, end))
+}
+
+fn parse_id(input: &str, line: &str) -> Result<usize> {
+    parse_at(input, line.trim(), "an ingredient ID")
+}
+
+fn parse(input: &str) -> Result<Inventory> {
     let iter = &mut input.lines();
 
-    let ranges: Vec<RangeInclusive<usize>> = iter
-        .map_while(|line| {
-            if line.trim().is_empty() {
-                None
-            } else {
-                let (start, end) = line.split_once('-')?;
-                Some(RangeInclusive::new(start.parse().ok()?, end.parse().ok()?))
-            }
-        })
-        .collect();
+    let fresh = iter
+        .take_while(|line| !line.trim().is_empty())
+        .map(|line| parse_range(input, line))
+        .collect::<Result<_>>()?;
+    let available = iter
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| parse_id(input, line))
+        .collect::<Result<_>>()?;
 
-    iter.filter_map(|line| {
-        let id = line.parse().ok()?;
-        ranges
-            .iter()
-            .find(|range| range.contains(&id))
-            .and(Some(()))
-    })
-    .count()
+    Ok(Inventory { fresh, available })
 }
 
-#[tracing::instrument(skip(input))]
-fn process2(input: &str) -> usize {
-    let iter = input
-        .lines()
-        .map_while(|line| {
-            if line.trim().is_empty() {
-                None
-            } else {
-                let (start, end) = line.split_once('-')?;
-                Some(RangeInclusive::new(
-                    start.parse::<usize>().ok()?,
-                    end.parse::<usize>().ok()?,
-                ))
-            }
-        })
-        .sorted_by_key(|range| *range.start());
+#[tracing::instrument(skip(inventory))]
+pub fn process(inventory: &Inventory) -> usize {
+    count_fresh(&inventory.fresh, inventory.available.iter().copied())
+}
+
+/// Number of `available` IDs in any of the `fresh` ranges.
+pub fn count_fresh(
+    fresh: &[RangeInclusive<usize>],
+    available: impl IntoIterator<Item = usize>,
+) -> usize {
+    available
+        .into_iter()
+        .filter(|id| fresh.iter().any(|range| range.contains(id)))
+        .count()
+}
+
+#[tracing::instrument(skip(ranges))]
+pub fn process2(ranges: &[RangeInclusive<usize>]) -> usize {
+    let iter = ranges.iter().cloned().sorted_by_key(|range| *range.start());
 
     let mut combined: usize = 0;
     let mut last_range: Option<RangeInclusive<_>> = None;
@@ -74,9 +102,83 @@ fn process2(input: &str) -> usize {
     combined
 }
 
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+    type Input<'a> = Inventory;
+    type Config = ();
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse(input)
+    }
+
+    fn part1(inventory: &Self::Input<'_>, _: &()) -> Result<Answer> {
+        Ok(process(inventory).into())
+    }
+
+    fn part2(inventory: &Self::Input<'_>, _: &()) -> Result<Option<Answer>> {
+        Ok(Some(process2(&inventory.fresh).into()))
+    }
+
+    /// Keeps only the ranges, part 2 stops reading after them.
+    fn stream(part: u8, reader: &mut dyn BufRead, _: &()) -> Result<Option<Answer>> {
+        let mut lines = stream::lines(reader);
+        let mut fresh = vec![];
+        for line in lines.by_ref() {
+            let line = line?;
+            if line.text.trim().is_empty() {
+                break;
+            }
+            fresh.push(line.parse(|line| parse_range(line, line))?);
+        }
+        match part {
+            1 => {
+                let available = lines
+                    .filter(|line| !matches!(line, Ok(line) if line.text.trim().is_empty()))
+                    .map(|line| line?.parse(|line| parse_id(line, line)));
+                Ok(Some(
+                    process_results(available, |ids| count_fresh(&fresh, ids))?.into(),
+                ))
+            }
+            2 => Ok(Some(process2(&fresh).into())),
+            _ => Ok(None),
+        }
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        const LIMIT: u64 = 1_000_000_000_000;
+        // together the ranges cover at most a tenth of the IDs
+        let width = LIMIT / 10 / size.max(1) as u64;
+        let fresh: Vec<_> = (0..size.max(1))
+            .map(|_| {
+                let start = rng.between(1, LIMIT);
+                (start, start + rng.between(0, width))
+            })
+            .collect();
+        let mut input: String = fresh
+            .iter()
+            .map(|(start, end)| format!("{start}-{end}\n"))
+            .collect();
+        input.push('\n');
+        for _ in 0..size {
+            // about half of the IDs are picked from a range so both counts are interesting
+            let id = if rng.chance(1, 2) {
+                let &(start, end) = rng.pick(&fresh);
+                rng.between(start, end)
+            } else {
+                rng.between(1, LIMIT)
+            };
+            input += &format!("{id}\n");
+        }
+        Some(input)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::verify::assert_answers;
 
     #[test_log::test]
     fn test_solution() {
@@ -91,16 +193,45 @@ mod test {
 11
 17
 32";
-        assert_eq!(process(input), 3);
-        assert_eq!(process(include_str!("../input/day05.txt")), 635);
-        assert_eq!(process2(input), 14);
+        let input = parse(input).unwrap();
+        assert_eq!(process(&input), 3);
+        assert_answers(5, 1, |input| process(&parse(input).unwrap()));
+        assert_eq!(process2(&input.fresh), 14);
     }
 
     #[test_log::test]
     fn test_part2() {
+        assert_answers(5, 2, |input| process2(&parse(input).unwrap().fresh));
+    }
+
+    #[test]
+    fn test_stream() {
+        let input = "3-5\n10-14\n16-20\n12-18\n\n1\n5\n8\n11\n17\n32\n";
+        let stream = |part, input: &str| Day05::stream(part, &mut input.as_bytes(), &());
+        assert_eq!(stream(1, input).unwrap(), Some(3.into()));
+        assert_eq!(stream(2, input).unwrap(), Some(14.into()));
+        assert_eq!(
+            stream(1, "3-5\n10_14\n\n1").unwrap_err().to_string(),
+            "2:1: expected `<start>-<end>`"
+        );
+        assert_eq!(
+            stream(1, "3-5\n\n1\n5a").unwrap_err().to_string(),
+            "4:1: expected an ingredient ID, found `5a`"
+        );
+        // part 2 never reads the IDs
+        assert_eq!(stream(2, "3-5\n\n1\n5a").unwrap(), Some(3.into()));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = parse("3-5\n10_14\n\n1").err().unwrap();
+        assert_eq!(err.to_string(), "2:1: expected `<start>-<end>`");
+        let err = parse("3-5\n5-3\n\n1").err().unwrap();
+        assert_eq!(err.to_string(), "2:1: range 5-3 ends before it starts");
+        let err = parse("3-5\n\n1\n5a").err().unwrap();
         assert_eq!(
-            process2(include_str!("../input/day05.txt")),
-            369761800782619
+            err.to_string(),
+            "4:1: expected an ingredient ID, found `5a`"
         );
     }
 }
@@ -1,86 +1,159 @@
 use tracing::info;
 
-fn process(input: &str, num_lines: usize) -> u64 {
-    let iter = &mut input.lines().map(|line| line.split_ascii_whitespace());
+use crate::{
+    answer::Answer,
+    error::{Error, Result, parse_at},
+    generator::Rng,
+    minimize::Shape,
+    solution::Solution,
+};
 
-    let numbers: Vec<Vec<_>> = iter
+pub fn process(input: &str, num_lines: usize) -> Result<u64> {
+    let iter = &mut input.lines();
+
+    let rows: Vec<Vec<&str>> = iter
         .take(num_lines)
-        .map(|line| {
-            line.map(|e| e.parse::<u64>().expect("not a number"))
-                .collect()
-        })
+        .map(|line| line.split_ascii_whitespace().collect())
+        .collect();
+    let numbers: Vec<Vec<u64>> = rows
+        .iter()
+        .map(|row| row.iter().map(|e| parse_at(input, e, "a number")).collect())
+        .collect::<Result<_>>()?;
+
+    let ops: Vec<&str> = iter
+        .next()
+        .ok_or_else(|| Error::at_offset(input, input.len(), "expected an operator row"))?
+        .split_ascii_whitespace()
         .collect();
+    if let Some(extra) = rows.iter().find_map(|row| row.get(ops.len())) {
+        return Err(Error::at(input, extra, "number has no operator below it"));
+    }
 
-    iter.next()
-        .expect("op row")
+    ops.into_iter()
         .enumerate()
-        .map(|(idx, line)| match line {
-            "+" => numbers.iter().map(|line| line[idx]).sum::<u64>(),
-            "*" => numbers
-                .iter()
-                .map(|line| line[idx])
-                .reduce(|acc, v| acc * v)
-                .expect("Empty iterator"),
-            op => panic!("Unknown op {op}"),
+        .map(|(idx, op)| {
+            let column = numbers.iter().map(|line| {
+                line.get(idx)
+                    .copied()
+                    .ok_or_else(|| Error::at(input, op, "operator has no numbers above it"))
+            });
+            match op {
+                "+" => column.sum::<Result<u64>>(),
+                "*" => column.product::<Result<u64>>(),
+                op => Err(Error::at(input, op, format!("unknown operator `{op}`"))),
+            }
         })
         .sum()
 }
 
-#[tracing::instrument(ret, skip(lines))]
-fn extract_numbers(lines: &[Vec<char>], start: usize, len: usize) -> impl Iterator<Item = u64> {
-    (start..(start + len)).map(|idx| {
-        lines
-            .iter()
-            .map(|line| line[idx])
-            .filter(|c| c != &' ')
-            .collect::<String>()
-            .parse::<u64>()
-            .expect("not a number")
-    })
-}
-
 #[tracing::instrument(skip(input))]
-fn process2(input: &str) -> u64 {
-    let lines: Vec<Vec<_>> = input
-        .chars()
-        .rev()
-        .collect::<String>()
-        .lines()
-        .filter(|line| !line.is_empty())
-        .map(|line| line.chars().collect())
-        .collect();
+pub fn process2(input: &str) -> Result<u64> {
+    let mut lines: Vec<&str> = input.lines().filter(|line| !line.is_empty()).collect();
+    let ops = lines
+        .pop()
+        .ok_or_else(|| Error::at_offset(input, 0, "expected an operator row"))?;
+    for line in &lines {
+        if let Some(pos) = line.find(|c: char| c != ' ' && !c.is_ascii_digit()) {
+            return Err(Error::at(input, &line[pos..], "expected a digit"));
+        }
+    }
+    if let Some(pos) = ops.find(|c| !matches!(c, ' ' | '+' | '*')) {
+        return Err(Error::at(input, &ops[pos..], "expected `+` or `*`"));
+    }
+    let width = lines.iter().chain([&ops]).map(|line| line.len()).max();
 
     let mut last_numbers = vec![];
     let mut result: u64 = 0;
 
-    info!(ops = ?lines[0]);
-    for (idx, op) in lines[0].iter().enumerate() {
-        let number = (1..lines.len())
-            .map(|row| lines[row][idx])
-            .filter(|v| v != &' ')
-            .rev()
+    info!(ops);
+    // cephalopod numbers are written in columns, read right to left
+    for idx in (0..width.unwrap_or(0)).rev() {
+        let number = lines
+            .iter()
+            .filter_map(|line| line.as_bytes().get(idx))
+            .filter(|v| **v != b' ')
+            .map(|v| *v as char)
             .collect::<String>();
 
         if !number.is_empty() {
             info!(number);
-            last_numbers.push(number.parse::<u64>().expect("not a number"));
-            match op {
-                ' ' => continue,
-                '+' => result += last_numbers.iter().sum::<u64>(),
-                '*' => result += last_numbers.iter().product::<u64>(),
-                c => unreachable!("Unknown op {c}"),
+            last_numbers.push(
+                number
+                    .parse::<u64>()
+                    .map_err(|_| Error::puzzle(format!("number {number} is too large")))?,
+            );
+            match ops.as_bytes().get(idx) {
+                Some(b'+') => result += last_numbers.iter().sum::<u64>(),
+                Some(b'*') => result += last_numbers.iter().product::<u64>(),
+                _ => continue,
             }
-            info!(?op, ?last_numbers, result);
+            info!(?last_numbers, result);
             last_numbers.clear();
         }
     }
     info!(result);
-    result
+    Ok(result)
+}
+
+#[derive(Default)]
+pub struct Config {
+    /// Number of rows holding numbers, all rows but the last one when `None`.
+    pub num_lines: Option<usize>,
+}
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u8 = 6;
+    // numbers are read row-wise in part 1 and column-wise in part 2
+    type Input<'a> = &'a str;
+    type Config = Config;
+    const SHAPE: Shape = Shape::Grid;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(input)
+    }
+
+    fn part1(input: &Self::Input<'_>, config: &Config) -> Result<Answer> {
+        let num_lines = config.num_lines.unwrap_or_else(|| {
+            let rows = input.lines().filter(|line| !line.trim().is_empty()).count();
+            rows.saturating_sub(1)
+        });
+        Ok(process(input, num_lines)?.into())
+    }
+
+    fn part2(input: &Self::Input<'_>, _: &Config) -> Result<Option<Answer>> {
+        Ok(Some(process2(input)?.into()))
+    }
+
+    /// Four rows of up to three digits keep every product, and the sum, in a `u64`.
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let mut rows = vec![String::new(); 5];
+        for problem in 0..size.max(1) {
+            let numbers: Vec<_> = (0..4).map(|_| rng.between(1, 999).to_string()).collect();
+            let width = numbers.iter().map(String::len).max().unwrap_or_default();
+            let left = rng.chance(1, 2);
+            if problem > 0 {
+                rows.iter_mut().for_each(|row| row.push(' '));
+            }
+            for (row, number) in rows.iter_mut().zip(&numbers) {
+                if left {
+                    *row += &format!("{number:<width$}");
+                } else {
+                    *row += &format!("{number:>width$}");
+                }
+            }
+            let op = if rng.chance(1, 2) { '+' } else { '*' };
+            rows[4] += &format!("{op:<width$}");
+        }
+        Some(rows.into_iter().map(|row| row + "\n").collect())
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::verify::assert_answers;
 
     #[test_log::test]
     fn test_part1() {
@@ -88,11 +161,10 @@ mod test {
  45 64  387 23 
   6 98  215 314
 *   +   *   +  ";
-        assert_eq!(process(input, 3), 4277556);
-        assert_eq!(
-            process(include_str!("../input/day06.txt"), 4),
-            6725216329103
-        );
+        assert_eq!(process(input, 3).unwrap(), 4277556);
+        assert_answers(6, 1, |input| {
+            Day06::part1(&input, &Config::default()).unwrap()
+        });
     }
 
     #[test_log::test]
@@ -101,7 +173,36 @@ mod test {
  45 64  387 23 
   6 98  215 314
 *   +   *   +  ";
-        assert_eq!(process2(input), 3263827);
-        assert_eq!(process2(include_str!("../input/day06.txt")), 10600728112865);
+        assert_eq!(process2(input).unwrap(), 3263827);
+        assert_answers(6, 2, |input| process2(input).unwrap());
+    }
+
+    #[test]
+    fn test_errors() {
+        let input = "123 328\n 45 6x\n*   +  ";
+        assert_eq!(
+            process(input, 2).unwrap_err().to_string(),
+            "2:5: expected a number, found `6x`"
+        );
+        assert_eq!(
+            process2(input).unwrap_err().to_string(),
+            "2:6: expected a digit"
+        );
+        assert_eq!(
+            process("1 2\n* -", 1).unwrap_err().to_string(),
+            "2:3: unknown operator `-`"
+        );
+        assert_eq!(
+            process2("1 2\n* -").unwrap_err().to_string(),
+            "2:3: expected `+` or `*`"
+        );
+        assert_eq!(
+            process("1 2\n*", 1).unwrap_err().to_string(),
+            "1:3: number has no operator below it"
+        );
+        assert_eq!(
+            process("1\n* +", 1).unwrap_err().to_string(),
+            "2:3: operator has no numbers above it"
+        );
     }
 }
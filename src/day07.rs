@@ -1,14 +1,69 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    io::BufRead,
+};
 
+use itertools::process_results;
 use tracing::info;
 
+use crate::{
+    answer::Answer,
+    error::{Error, Result},
+    generator::Rng,
+    grid::Grid,
+    minimize::Shape,
+    solution::Solution,
+    stream,
+};
+
+fn parse(input: &str) -> Result<Grid<char>> {
+    let manifold = Grid::parse(input, |c| matches!(c, '.' | 'S' | '^').then_some(c))?;
+    if let Some((_, line)) = manifold
+        .column(0)
+        .zip(input.lines())
+        .find(|(c, _)| **c == '^')
+    {
+        // a split beam would leave the manifold on the left
+        return Err(Error::at(input, line, "splitter on the edge"));
+    }
+    Ok(manifold)
+}
+
+/// One row of the manifold checked the way [`parse`] checks the whole of it, `width`
+/// is set by the first row.
+fn parse_row(input: &str, line: &str, width: &mut Option<usize>) -> Result<Vec<char>> {
+    let row = line
+        .char_indices()
+        .map(|(pos, c)| match c {
+            '.' | 'S' | '^' => Ok(c),
+            _ => Err(Error::at(input, &line[pos..], format!("unexpected `{c}`"))),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if *width.get_or_insert(row.len()) != row.len() {
+        return Err(Error::at(
+            input,
+            line,
+            format!("expected {} columns", width.unwrap_or_default()),
+        ));
+    }
+    if row.first() == Some(&'^') {
+        return Err(Error::at(input, line, "splitter on the edge"));
+    }
+    Ok(row)
+}
+
 #[tracing::instrument(skip(input))]
-fn process_part1(input: &str) -> u32 {
+pub fn process_part1(input: &Grid<char>) -> u32 {
+    count_splits(input.rows())
+}
+
+/// Splits of the beam going down `rows`.
+fn count_splits(rows: impl IntoIterator<Item = impl AsRef<[char]>>) -> u32 {
     let mut beams = HashSet::new();
     let mut num_splits = 0;
 
-    for line in input.lines() {
-        for (pos, ch) in line.chars().enumerate() {
+    for (row, line) in rows.into_iter().enumerate() {
+        for (pos, ch) in line.as_ref().iter().enumerate() {
             match ch {
                 'S' => {
                     beams.insert(pos);
@@ -22,17 +77,22 @@ fn process_part1(input: &str) -> u32 {
                 _ => {}
             }
         }
-        info!(?beams, num_splits, line);
+        info!(?beams, num_splits, row);
     }
     num_splits
 }
 
 #[tracing::instrument(skip(input))]
-fn process_part2(input: &str) -> u64 {
+pub fn process_part2(input: &Grid<char>) -> u64 {
+    count_timelines(input.rows())
+}
+
+/// Paths a single particle can take down `rows`.
+fn count_timelines(rows: impl IntoIterator<Item = impl AsRef<[char]>>) -> u64 {
     let mut beams = HashMap::new();
 
-    for line in input.lines() {
-        for (pos, ch) in line.chars().enumerate() {
+    for (row, line) in rows.into_iter().enumerate() {
+        for (pos, ch) in line.as_ref().iter().enumerate() {
             match ch {
                 'S' => {
                     beams.insert(pos, 1);
@@ -52,14 +112,81 @@ fn process_part2(input: &str) -> u64 {
                 _ => {}
             }
         }
-        info!(?beams, line);
+        info!(?beams, row);
     }
     beams.values().sum()
 }
 
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u8 = 7;
+    type Input<'a> = Grid<char>;
+    type Config = ();
+    const SHAPE: Shape = Shape::Grid;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input<'_>, _: &()) -> Result<Answer> {
+        Ok(process_part1(input).into())
+    }
+
+    fn part2(input: &Self::Input<'_>, _: &()) -> Result<Option<Answer>> {
+        Ok(Some(process_part2(input).into()))
+    }
+
+    fn stream(part: u8, reader: &mut dyn BufRead, _: &()) -> Result<Option<Answer>> {
+        let mut width = None;
+        let rows =
+            stream::lines(reader).map(|line| line?.parse(|line| parse_row(line, line, &mut width)));
+        let answer = match part {
+            1 => process_results(rows, |rows| count_splits(rows))?.into(),
+            2 => process_results(rows, |rows| count_timelines(rows))?.into(),
+            _ => return Ok(None),
+        };
+        match width {
+            Some(width) if width > 0 => Ok(Some(answer)),
+            _ => Err(Error::at_offset("", 0, "expected a grid")),
+        }
+    }
+
+    /// About `size` columns, with blank rows between the splitter rows as in the puzzle.
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let width = size.max(3);
+        let splitter_rows = width / 2;
+        // sparse enough that the number of timelines stays well inside a `u64`
+        let odds = (splitter_rows as u64 / 16).max(3);
+        let mut start = vec!['.'; width];
+        start[width / 2] = 'S';
+        let mut rows = vec![start];
+        for _ in 0..splitter_rows {
+            rows.push(vec!['.'; width]);
+            // never on an edge, a split beam would leave the manifold
+            let row = (0..width)
+                .map(|pos| match pos {
+                    0 => '.',
+                    pos if pos == width - 1 => '.',
+                    _ if rng.chance(1, odds) => '^',
+                    _ => '.',
+                })
+                .collect();
+            rows.push(row);
+        }
+        rows.push(vec!['.'; width]);
+        Some(
+            rows.into_iter()
+                .map(|row| row.into_iter().chain(['\n']).collect::<String>())
+                .collect(),
+        )
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::verify::assert_answers;
 
     #[test_log::test]
     fn test_part1() {
@@ -79,8 +206,8 @@ mod test {
 ...............
 .^.^.^.^.^...^.
 ...............";
-        assert_eq!(process_part1(input), 21);
-        assert_eq!(process_part1(include_str!("../input/day07.txt")), 1646);
+        assert_eq!(process_part1(&parse(input).unwrap()), 21);
+        assert_answers(7, 1, |input| process_part1(&parse(input).unwrap()));
     }
     #[test_log::test]
     fn test_part2() {
@@ -100,10 +227,59 @@ mod test {
 ...............
 .^.^.^.^.^...^.
 ...............";
-        assert_eq!(process_part2(input), 40);
+        assert_eq!(process_part2(&parse(input).unwrap()), 40);
+        assert_answers(7, 2, |input| process_part2(&parse(input).unwrap()));
+    }
+
+    #[test]
+    fn test_stream() {
+        let input = ".......S.......
+...............
+.......^.......
+...............
+......^.^......
+...............
+.....^.^.^.....
+...............
+....^.^...^....
+...............
+...^.^...^.^...
+...............
+..^...^.....^..
+...............
+.^.^.^.^.^...^.
+...............
+";
+        let stream = |part, input: &str| Day07::stream(part, &mut input.as_bytes(), &());
+        assert_eq!(stream(1, input).unwrap(), Some(21.into()));
+        assert_eq!(stream(2, input).unwrap(), Some(40.into()));
+        assert_eq!(
+            stream(1, "..S..\n..^.|").unwrap_err().to_string(),
+            "2:5: unexpected `|`"
+        );
+        assert_eq!(
+            stream(2, "..S..\n^.^..").unwrap_err().to_string(),
+            "2:1: splitter on the edge"
+        );
+        assert_eq!(
+            stream(2, "..S..\n...").unwrap_err().to_string(),
+            "2:1: expected 5 columns"
+        );
+        assert_eq!(
+            stream(1, "").unwrap_err().to_string(),
+            "1:1: expected a grid"
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            parse("..S..\n..^.|").unwrap_err().to_string(),
+            "2:5: unexpected `|`"
+        );
         assert_eq!(
-            process_part2(include_str!("../input/day07.txt")),
-            32451134474991
+            parse("..S..\n^.^..").unwrap_err().to_string(),
+            "2:1: splitter on the edge"
         );
     }
 }
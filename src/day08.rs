@@ -1,9 +1,18 @@
-use std::collections::{BinaryHeap, HashSet};
+use std::collections::BinaryHeap;
 
 use glam::I64Vec3;
 use itertools::Itertools;
 use tracing::info;
 
+use crate::{
+    answer::Answer,
+    error::{Error, Result},
+    generator::Rng,
+    geometry::{Metric, parse_point},
+    graph::{Graph, Interner, UnionFind},
+    solution::Solution,
+};
+
 #[derive(Debug, Clone, Copy)]
 struct Pair {
     a: I64Vec3,
@@ -11,11 +20,8 @@ struct Pair {
 }
 
 impl Pair {
-    fn distance(&self) -> i64 {
-        (self.a - self.b).length_squared()
-    }
-    fn is_connected(&self, other: Pair) -> bool {
-        self.a == other.a || self.a == other.b || self.b == other.a
+    fn distance(&self) -> u64 {
+        Metric::SquaredEuclidean.distance(self.a, self.b)
     }
 }
 impl PartialEq for Pair {
@@ -35,143 +41,120 @@ impl Ord for Pair {
     }
 }
 
-#[tracing::instrument(skip(input))]
-fn process_part1(input: &str, connections: usize) -> u64 {
-    let boxes: Vec<_> = input
+fn parse(input: &str) -> Result<Vec<I64Vec3>> {
+    input
         .lines()
-        .map(|line| {
-            I64Vec3::from_slice(
-                &line
-                    .split(',')
-                    .filter_map(|pos| pos.parse::<i64>().ok())
-                    .collect::<Vec<i64>>(),
-            )
-        })
-        .collect();
+        .map(|line| Ok(I64Vec3::from_array(parse_point(input, line)?)))
+        .collect()
+}
 
+/// All pairs of boxes, closest first.
+fn closest_pairs(boxes: &[I64Vec3]) -> impl Iterator<Item = Pair> {
     let mut heap: BinaryHeap<Pair> = boxes
         .iter()
-        .combinations(2)
-        .map(|pair| Pair {
-            a: *pair[0],
-            b: *pair[1],
-        })
+        .tuple_combinations()
+        .map(|(&a, &b)| Pair { a, b })
         .collect();
+    std::iter::from_fn(move || heap.pop())
+}
 
-    let mut clusters: Vec<HashSet<I64Vec3>> = vec![];
-    let mut conns_made = 0;
-    while conns_made < connections
-        && let Some(pair) = heap.pop()
-    {
-        let clus1 = clusters
-            .iter()
-            .position(|cluster| cluster.contains(&pair.a));
-        let clus2 = clusters
-            .iter()
-            .position(|cluster| cluster.contains(&pair.b));
-        match (clus1, clus2) {
-            (Some(c1), Some(c2)) if c1 != c2 => {
-                let first = c1.min(c2);
-                let second = c1.max(c2);
-                info!(merge = ?(first, second));
-
-                let values = clusters.remove(second);
-                clusters[first].extend(values);
-            }
-            (None, None) => {
-                info!(new_cluster = ?pair);
-                clusters.push(HashSet::from([pair.a, pair.b]))
-            }
-            (None, Some(c)) => {
-                info!(position = c, append = ?pair);
-                clusters[c].insert(pair.a);
-            }
-            (Some(c), None) => {
-                info!(position = c, append = ?pair);
-                clusters[c].insert(pair.b);
-            }
-            _ => {}
-        }
-        conns_made += 1;
-    }
-    clusters.sort_by_key(|cluster| cluster.len());
-    clusters
+#[tracing::instrument(skip(boxes))]
+pub fn process_part1(boxes: &[I64Vec3], connections: usize) -> u64 {
+    let ids: Interner<_> = boxes.iter().copied().collect();
+    let circuits = Graph::undirected(
+        ids.len(),
+        closest_pairs(boxes)
+            .take(connections)
+            .map(|pair| (ids[pair.a], ids[pair.b])),
+    )
+    .components();
+
+    circuits
         .iter()
-        .rev()
         .map(|c| c.len() as u64)
-        .filter(|l| *l > 0)
+        .sorted_unstable()
+        .rev()
         .take(3)
         .inspect(|l| info!(l))
         .product()
 }
 
-#[tracing::instrument(skip(input))]
-fn process_part2(input: &str) -> i64 {
-    let boxes: Vec<_> = input
-        .lines()
-        .map(|line| {
-            I64Vec3::from_slice(
-                &line
-                    .split(',')
-                    .filter_map(|pos| pos.parse::<i64>().ok())
-                    .collect::<Vec<i64>>(),
-            )
-        })
-        .collect();
-    let size = boxes.len();
+#[tracing::instrument(skip(boxes))]
+pub fn process_part2(boxes: &[I64Vec3]) -> Result<i64> {
+    let ids: Interner<_> = boxes.iter().copied().collect();
+    let mut circuits = UnionFind::new(ids.len());
+    for pair in closest_pairs(boxes) {
+        if circuits.union(ids[pair.a], ids[pair.b]) {
+            info!(merge = ?pair, left = circuits.sets());
+            if circuits.sets() == 1 {
+                return Ok(pair.a.x * pair.b.x);
+            }
+        }
+    }
+    Err(Error::puzzle("boxes never form a single circuit"))
+}
 
-    let mut heap: BinaryHeap<Pair> = boxes
-        .iter()
-        .combinations(2)
-        .map(|pair| Pair {
-            a: *pair[0],
-            b: *pair[1],
-        })
-        .collect();
+pub struct Config {
+    /// Shortest connections made in part 1.
+    pub connections: usize,
+}
 
-    let mut clusters: Vec<HashSet<I64Vec3>> = vec![];
-    while let Some(pair) = heap.pop() {
-        let clus1 = clusters
-            .iter()
-            .position(|cluster| cluster.contains(&pair.a));
-        let clus2 = clusters
-            .iter()
-            .position(|cluster| cluster.contains(&pair.b));
-        match (clus1, clus2) {
-            (Some(c1), Some(c2)) if c1 != c2 => {
-                info!(merge = ?(c1, c2));
-                let values2 = clusters[c2].clone();
-                clusters[c1].extend(values2);
-                clusters.remove(c2);
-            }
-            (None, None) => {
-                info!(new_cluster = ?pair);
-                clusters.push(HashSet::from([pair.a, pair.b]))
-            }
-            (None, Some(c)) => {
-                info!(position = c, append = ?pair);
-                clusters[c].insert(pair.a);
-            }
-            (Some(c), None) => {
-                info!(position = c, append = ?pair);
-                clusters[c].insert(pair.b);
+impl Default for Config {
+    fn default() -> Self {
+        Self { connections: 1000 }
+    }
+}
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    const DAY: u8 = 8;
+    type Input<'a> = Vec<I64Vec3>;
+    type Config = Config;
+
+    fn with_setting(_: Config, name: &str, value: &str) -> Result<Config> {
+        match name {
+            "connections" => {
+                let connections = value.parse().map_err(|_| {
+                    Error::puzzle(format!("setting `{name}` expects a count, got `{value}`"))
+                })?;
+                Ok(Config { connections })
             }
-            _ => {}
+            _ => Err(Error::puzzle(format!("day 8 has no setting `{name}`"))),
         }
-        info!(clen = clusters[0].len());
-        if clusters[0].len() == size {
-            return pair.a.x * pair.b.x;
+    }
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse(input)
+    }
+
+    fn part1(boxes: &Self::Input<'_>, config: &Config) -> Result<Answer> {
+        Ok(process_part1(boxes, config.connections).into())
+    }
+
+    fn part2(boxes: &Self::Input<'_>, _: &Config) -> Result<Option<Answer>> {
+        Ok(Some(process_part2(boxes)?.into()))
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let mut seen = std::collections::HashSet::new();
+        let mut input = String::new();
+        while seen.len() < size {
+            let point = [(); 3].map(|_| rng.between(0, 99_999));
+            if seen.insert(point) {
+                let [x, y, z] = point;
+                input += &format!("{x},{y},{z}\n");
+            }
         }
+        Some(input)
     }
-    info!(size);
-    clusters.iter().for_each(|c| info!(len = c.len()));
-    0
 }
 
 #[cfg(test)]
 mod test {
 
     use super::*;
+    use crate::verify::assert_answers;
 
     #[test]
     fn test_eq() {
@@ -209,11 +192,8 @@ mod test {
 984,92,344
 425,690,689";
 
-        assert_eq!(process_part1(input, 10), 40);
-        assert_eq!(
-            process_part1(include_str!("../input/day08.txt"), 1000),
-            96672
-        );
+        assert_eq!(process_part1(&parse(input).unwrap(), 10), 40);
+        assert_answers(8, 1, |input| process_part1(&parse(input).unwrap(), 1000));
     }
 
     #[test_log::test]
@@ -239,7 +219,20 @@ mod test {
 984,92,344
 425,690,689";
 
-        assert_eq!(process_part2(input), 25272);
-        assert_eq!(process_part2(include_str!("../input/day08.txt")), 22517595);
+        assert_eq!(process_part2(&parse(input).unwrap()).unwrap(), 25272);
+        assert_answers(8, 2, |input| process_part2(&parse(input).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            parse("1,2,3\n4,5").unwrap_err().to_string(),
+            "2:1: expected `x,y,z`"
+        );
+        assert_eq!(
+            parse("1,2,3\n4,five,6").unwrap_err().to_string(),
+            "2:3: expected a coordinate, found `five`"
+        );
+        assert!(process_part2(&parse("1,2,3").unwrap()).is_err());
     }
 }
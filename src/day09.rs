@@ -1,44 +1,51 @@
-use glam::{U64Vec2, u64vec2};
+use glam::{U64Vec2, USizeVec2};
 use itertools::Itertools;
 
-fn process_part1(input: &str) -> u64 {
+use crate::{
+    answer::Answer,
+    differential::Check,
+    error::{Error, Result},
+    generator::Rng,
+    geometry::{Rect, parse_point},
+    grid::Grid,
+    solution::Solution,
+};
+
+fn parse(input: &str) -> Result<Vec<U64Vec2>> {
     input
         .lines()
-        .map(|line| {
-            line.split_once(',')
-                .and_then(|(x, y)| Some(u64vec2(x.parse::<u64>().ok()?, y.parse::<u64>().ok()?)))
-                .expect("not a number pair")
-        })
+        .map(|line| Ok(U64Vec2::from_array(parse_point(input, line)?)))
+        .collect()
+}
+
+pub fn process_part1(tiles: &[U64Vec2]) -> Result<u64> {
+    tiles
+        .iter()
         .tuple_combinations()
-        .map(|(a, b)| (a.x.abs_diff(b.x) + 1) * (a.y.abs_diff(b.y) + 1))
+        .map(|(a, b)| Rect::from_corners(*a, *b).area())
         .max()
-        .expect("Iterator is empty")
+        .ok_or_else(|| Error::puzzle("expected at least two red tiles"))
 }
 
-fn intersects((a, b): (&U64Vec2, &U64Vec2), (c, d): (&U64Vec2, &U64Vec2)) -> bool {
-    let h1 = u64vec2(a.x.min(b.x), a.x.max(b.x));
-    let h2 = u64vec2(c.x.min(d.x), c.x.max(d.x));
-
-    let v1 = u64vec2(a.y.min(b.y), a.y.max(b.y));
-    let v2 = u64vec2(c.y.min(d.y), c.y.max(d.y));
-
-    h1.y > h2.x && h2.y > h1.x && v1.y > v2.x && v2.y > v1.x
+/// Whether `point`, in doubled coordinates so the centres of rectangles are whole,
+/// is on the loop of `lines` or inside it.
+fn inside(lines: &[(U64Vec2, U64Vec2)], point: U64Vec2) -> bool {
+    let mut crossings = 0;
+    for &(a, b) in lines {
+        let (a, b) = (a * 2, b * 2);
+        if Rect::from_corners(a, b).contains(point) {
+            return true;
+        }
+        // a ray towards +x passes the vertical edges, each counted at its lower end
+        if a.x == b.x && a.x > point.x && (a.y.min(b.y)..a.y.max(b.y)).contains(&point.y) {
+            crossings += 1;
+        }
+    }
+    crossings % 2 == 1
 }
 
-#[tracing::instrument(skip(input))]
-fn process_part2(input: &str) -> u64 {
-    let tiles: Vec<_> = input
-        .lines()
-        .map(|line| {
-            U64Vec2::from_slice(
-                line.split(',')
-                    .map(|v| v.parse::<u64>().expect("not a number"))
-                    .collect::<Vec<_>>()
-                    .as_slice(),
-            )
-        })
-        .collect();
-
+#[tracing::instrument(skip(tiles))]
+pub fn process_part2(tiles: &[U64Vec2]) -> Result<u64> {
     let lines: Vec<_> = tiles
         .iter()
         .circular_tuple_windows()
@@ -52,16 +59,147 @@ fn process_part2(input: &str) -> u64 {
             !lines
                 .iter()
                 .filter(|(c, d)| c != a && c != b && d != a && d != b)
-                .any(|(c, d)| intersects((a, b), (c, d)))
+                .any(|(c, d)| Rect::from_corners(*a, *b).crossed_by(*c, *d))
         })
-        .map(|(a, b)| (a.x.abs_diff(b.x) + 1) * (a.y.abs_diff(b.y) + 1))
+        // with no edge through it the rectangle is all inside or all outside, a notch
+        // of the loop can be bounded by edges along its sides only
+        .filter(|&(a, b)| inside(&lines, a + b))
+        .map(|(a, b)| Rect::from_corners(*a, *b).area())
+        .max()
+        .ok_or_else(|| Error::puzzle("expected at least two red tiles"))
+}
+
+/// [`process_part2`] on a grid with a cell for every distinct coordinate and for every
+/// gap between two, where a prefix sum of the cells outside the loop tells in constant
+/// time whether a rectangle leaves it.
+pub fn process_part2_compressed(tiles: &[U64Vec2]) -> Result<u64> {
+    let xs: Vec<_> = tiles
+        .iter()
+        .map(|tile| tile.x)
+        .sorted_unstable()
+        .dedup()
+        .collect();
+    let ys: Vec<_> = tiles
+        .iter()
+        .map(|tile| tile.y)
+        .sorted_unstable()
+        .dedup()
+        .collect();
+    // the border of empty cells around the loop is where the outside starts
+    let cell = |tile: U64Vec2| {
+        let index =
+            |values: &[u64], value| 2 * values.binary_search(&value).unwrap_or_default() + 1;
+        USizeVec2::new(index(&xs, tile.x), index(&ys, tile.y))
+    };
+    let mut walls = Grid::new(2 * xs.len() + 1, 2 * ys.len() + 1, false);
+    for (a, b) in tiles.iter().circular_tuple_windows() {
+        for pos in Rect::from_corners(cell(*a), cell(*b)).points() {
+            walls[pos] = true;
+        }
+    }
+
+    let mut outside = Grid::new(walls.width(), walls.height(), false);
+    outside[USizeVec2::ZERO] = true;
+    let mut todo = vec![USizeVec2::ZERO];
+    while let Some(pos) = todo.pop() {
+        for next in walls.neighbours4(pos) {
+            if !walls[next] && !outside[next] {
+                outside[next] = true;
+                todo.push(next);
+            }
+        }
+    }
+
+    // cells outside in the rows above and columns left of each position
+    let mut sums = Grid::new(outside.width() + 1, outside.height() + 1, 0_usize);
+    for pos in outside.positions() {
+        sums[pos + 1] =
+            usize::from(outside[pos]) + sums[pos + USizeVec2::X] + sums[pos + USizeVec2::Y]
+                - sums[pos];
+    }
+    let count_outside = |rect: Rect<USizeVec2>| {
+        let (min, max) = (rect.min, rect.max + 1);
+        sums[max] + sums[min]
+            - sums[USizeVec2::new(min.x, max.y)]
+            - sums[USizeVec2::new(max.x, min.y)]
+    };
+
+    tiles
+        .iter()
+        .tuple_combinations()
+        .filter(|&(a, b)| count_outside(Rect::from_corners(cell(*a), cell(*b))) == 0)
+        .map(|(a, b)| Rect::from_corners(*a, *b).area())
         .max()
-        .expect("Iterator is empty")
+        .ok_or_else(|| Error::puzzle("expected at least two red tiles"))
+}
+
+const CHECKS: &[Check] = &[Check {
+    name: "day09::part2",
+    day: 9,
+    size: 40,
+    reference: |input| Ok(process_part2(&parse(input)?)?.into()),
+    optimized: |input| Ok(process_part2_compressed(&parse(input)?)?.into()),
+}];
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u8 = 9;
+    type Input<'a> = Vec<U64Vec2>;
+    type Config = ();
+    const CHECKS: &'static [Check] = CHECKS;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse(input)
+    }
+
+    fn part1(tiles: &Self::Input<'_>, _: &()) -> Result<Answer> {
+        Ok(process_part1(tiles)?.into())
+    }
+
+    fn part2(tiles: &Self::Input<'_>, _: &()) -> Result<Option<Answer>> {
+        Ok(Some(process_part2(tiles)?.into()))
+    }
+
+    /// A loop of about `size` red tiles around columns of random heights, the top edge
+    /// always above the bottom one so the loop never touches itself.
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        const MIDDLE: u64 = 50_000;
+        let columns = (size / 4).max(1);
+        let mut xs = vec![rng.between(1, 2000)];
+        for _ in 0..columns {
+            xs.push(xs[xs.len() - 1] + rng.between(1, 2000));
+        }
+        // neighbouring columns differ so no tile sits in the middle of a straight edge
+        let mut heights = |low, high| {
+            let mut heights: Vec<u64> = vec![];
+            while heights.len() < columns {
+                let height = rng.between(low, high);
+                if heights.last() != Some(&height) {
+                    heights.push(height);
+                }
+            }
+            heights
+        };
+        let (tops, bottoms) = (heights(MIDDLE + 1, 2 * MIDDLE), heights(1, MIDDLE - 1));
+
+        let mut tiles = vec![];
+        for (i, &top) in tops.iter().enumerate() {
+            tiles.push((xs[i], top));
+            tiles.push((xs[i + 1], top));
+        }
+        for (i, &bottom) in bottoms.iter().enumerate().rev() {
+            tiles.push((xs[i + 1], bottom));
+            tiles.push((xs[i], bottom));
+        }
+        Some(tiles.iter().map(|(x, y)| format!("{x},{y}\n")).collect())
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::verify::assert_answers;
 
     #[test]
     fn test_part1() {
@@ -73,11 +211,8 @@ mod test {
 2,5
 2,3
 7,3";
-        assert_eq!(process_part1(input), 50);
-        assert_eq!(
-            process_part1(include_str!("../input/day09.txt")),
-            4776487744
-        );
+        assert_eq!(process_part1(&parse(input).unwrap()).unwrap(), 50);
+        assert_answers(9, 1, |input| process_part1(&parse(input).unwrap()).unwrap());
     }
 
     #[test_log::test]
@@ -90,10 +225,38 @@ mod test {
 2,5
 2,3
 7,3";
-        assert_eq!(process_part2(input), 24);
+        assert_eq!(process_part2(&parse(input).unwrap()).unwrap(), 24);
+        assert_answers(9, 2, |input| process_part2(&parse(input).unwrap()).unwrap());
+        assert_eq!(
+            process_part2_compressed(&parse(input).unwrap()).unwrap(),
+            24
+        );
+        assert_answers(9, 2, |input| {
+            process_part2_compressed(&parse(input).unwrap()).unwrap()
+        });
+    }
+
+    #[test]
+    fn test_notch() {
+        // a U whose notch, from (3,3) to (9,9), is bounded by edges along its sides only
+        let input = "1,1\n11,1\n11,9\n9,9\n9,3\n3,3\n3,9\n1,9";
+        assert_eq!(process_part2(&parse(input).unwrap()).unwrap(), 27);
+        assert_eq!(
+            process_part2_compressed(&parse(input).unwrap()).unwrap(),
+            27
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            parse("7,1\n11;1").unwrap_err().to_string(),
+            "2:1: expected `x,y`"
+        );
         assert_eq!(
-            process_part2(include_str!("../input/day09.txt")),
-            1560299548
+            parse("7,1\n11,-1").unwrap_err().to_string(),
+            "2:4: expected a coordinate, found `-1`"
         );
+        assert!(process_part1(&parse("7,1").unwrap()).is_err());
     }
 }
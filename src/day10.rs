@@ -1,26 +1,73 @@
-use std::collections::HashMap;
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, VecDeque},
+    io::BufRead,
+};
 
-use itertools::Itertools;
+use itertools::{Itertools, process_results};
 use nom::{
     IResult, Parser,
     branch::alt,
-    character::complete::{self, line_ending},
+    character::complete,
+    combinator::all_consuming,
     multi::{many1, separated_list1},
     sequence::delimited,
 };
 use tracing::{info, warn};
 
+use crate::{
+    answer::Answer,
+    differential::Check,
+    error::{Error, Result},
+    generator::Rng,
+    parallel,
+    solution::Solution,
+    stream,
+};
+
 type Button = Vec<usize>;
 
 #[derive(Debug)]
-struct Problem {
+pub struct Problem {
     diagram: Vec<bool>,
     buttons: Vec<Button>,
     joltage: Vec<usize>,
 }
 
-fn parse(input: &str) -> IResult<&str, Vec<Problem>> {
-    (separated_list1(line_ending, parse_problem)).parse(input)
+fn parse_machine(input: &str, line: &str) -> Result<Problem> {
+    let (_, problem) = all_consuming(parse_problem)
+        .parse(line)
+        .map_err(|err| Error::nom(input, err))?;
+
+    let lights = problem.diagram.len();
+    if problem
+        .buttons
+        .iter()
+        .flatten()
+        .any(|&light| light >= lights)
+    {
+        return Err(Error::at(
+            input,
+            line,
+            format!("button wired past {lights} lights"),
+        ));
+    }
+    if problem.joltage.len() != lights {
+        return Err(Error::at(
+            input,
+            line,
+            format!("expected {lights} joltage levels"),
+        ));
+    }
+    Ok(problem)
+}
+
+fn parse(input: &str) -> Result<Vec<Problem>> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| parse_machine(input, line))
+        .collect()
 }
 
 #[tracing::instrument]
@@ -76,33 +123,94 @@ fn check_diagram(buttons: &[&Vec<usize>], diagram: &[bool]) -> bool {
         .all(|&v| !v)
 }
 
-#[tracing::instrument(skip(input))]
-fn process_part1(input: &str) -> usize {
-    let (_, problems) = parse(input).unwrap();
-    problems
+/// Fewest presses that turn on the lights of `problem`, `None` if none do.
+fn fewest_presses(problem: &Problem) -> Option<usize> {
+    info!(diagram = ?problem.diagram);
+    problem
+        .buttons
         .iter()
-        .map(|problem| {
-            info!(diagram = ?problem.diagram);
-            problem
-                .buttons
-                .iter()
-                .powerset()
-                .filter_map(|buttons| {
-                    check_diagram(&buttons, &problem.diagram).then_some(buttons.len())
-                })
-                .min()
-                .expect("min() failed")
+        .powerset()
+        .filter_map(|buttons| check_diagram(&buttons, &problem.diagram).then_some(buttons.len()))
+        .min()
+}
+
+fn total_presses(presses: impl IntoIterator<Item = Option<usize>>) -> Result<usize> {
+    presses
+        .into_iter()
+        .enumerate()
+        .map(|(i, presses)| {
+            presses.ok_or_else(|| Error::puzzle(format!("machine #{i} can't be configured")))
         })
         .sum()
 }
 
+#[tracing::instrument(skip(problems))]
+pub fn process_part1(problems: impl IntoIterator<Item = impl Borrow<Problem>>) -> Result<usize> {
+    total_presses(
+        problems
+            .into_iter()
+            .map(|problem| fewest_presses(problem.borrow())),
+    )
+}
+
+/// [`process_part1`] with the machines split between `jobs` workers.
+#[tracing::instrument(skip(problems))]
+pub fn process_part1_parallel(problems: &[Problem], jobs: usize) -> Result<usize> {
+    total_presses(parallel::map(problems, jobs, |_, problem| {
+        fewest_presses(problem)
+    }))
+}
+
+/// [`fewest_presses`] by a breadth-first search over the `2^lights` patterns the lights
+/// can show, rather than over the `2^buttons` sets of buttons.
+fn fewest_presses_bfs(problem: &Problem) -> Option<usize> {
+    let mask =
+        |lights: &mut dyn Iterator<Item = usize>| lights.fold(0, |mask, light| mask | 1 << light);
+    let target = mask(&mut problem.diagram.iter().positions(|&on| on));
+    let buttons: Vec<usize> = problem
+        .buttons
+        .iter()
+        .map(|button| mask(&mut button.iter().copied()))
+        .collect();
+
+    let mut presses = vec![None; 1 << problem.diagram.len()];
+    presses[0] = Some(0);
+    let mut queue = VecDeque::from([0]);
+    while let Some(lights) = queue.pop_front() {
+        let pressed = presses[lights]?;
+        if lights == target {
+            return Some(pressed);
+        }
+        for button in &buttons {
+            let next = lights ^ button;
+            if presses[next].is_none() {
+                presses[next] = Some(pressed + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+pub fn process_part1_bfs(problems: &[Problem]) -> Result<usize> {
+    total_presses(problems.iter().map(fewest_presses_bfs))
+}
+
+const CHECKS: &[Check] = &[Check {
+    name: "day10::part1",
+    day: 10,
+    size: 20,
+    reference: |input| Ok(process_part1(parse(input)?)?.into()),
+    optimized: |input| Ok(process_part1_bfs(&parse(input)?)?.into()),
+}];
+
 fn find_joltage_solution_rec<'a>(
     buttons: &'a [Button],
     joltage: &[usize],
     cache: &mut HashMap<Vec<bool>, Vec<Vec<&'a Button>>>,
-) -> usize {
+) -> Option<usize> {
     if joltage.iter().sum::<usize>() == 0 {
-        return 0;
+        return Some(0);
     }
     info!(?joltage);
     let diagram: Vec<_> = joltage.iter().map(|&j| !j.is_multiple_of(2)).collect();
@@ -136,45 +244,145 @@ fn find_joltage_solution_rec<'a>(
                 .collect::<Result<Vec<usize>, ()>>()
                 .ok()?; // skipping this variant of failed to subtract buttons from joltage
             info!(?variant, ?rem_half_joltage);
-            Some(variant.len() + 2 * find_joltage_solution_rec(buttons, &rem_half_joltage, cache))
+            Some(variant.len() + 2 * find_joltage_solution_rec(buttons, &rem_half_joltage, cache)?)
         })
-        .min()
-        .unwrap_or(1_000_000);
+        .min();
 
-    info!(?joltage, result);
+    info!(?joltage, ?result);
     result
 }
 
-fn find_joltage_solution(problem: &Problem) -> usize {
+/// Fewest presses that reach the joltage of `problem`, `None` if none do.
+fn find_joltage_solution(problem: &Problem) -> Option<usize> {
     let mut cache = HashMap::new();
     find_joltage_solution_rec(&problem.buttons, &problem.joltage, &mut cache)
 }
 
-#[tracing::instrument(skip(input))]
-fn process_part2(input: &str) -> usize {
-    let (_, problems) = parse(input).unwrap();
-    problems
-        .iter()
-        .enumerate()
-        .map(|(i, problem)| {
-            let value = find_joltage_solution(problem);
-            info!("Problem#{i}: {value}");
-            value
-        })
-        .sum()
+fn joltage_presses(i: usize, problem: &Problem) -> Option<usize> {
+    let value = find_joltage_solution(problem);
+    info!("Problem#{i}: {value:?}");
+    value
+}
+
+#[tracing::instrument(skip(problems))]
+pub fn process_part2(problems: impl IntoIterator<Item = impl Borrow<Problem>>) -> Result<usize> {
+    total_presses(
+        problems
+            .into_iter()
+            .enumerate()
+            .map(|(i, problem)| joltage_presses(i, problem.borrow())),
+    )
+}
+
+/// [`process_part2`] with the machines split between `jobs` workers.
+#[tracing::instrument(skip(problems))]
+pub fn process_part2_parallel(problems: &[Problem], jobs: usize) -> Result<usize> {
+    total_presses(parallel::map(problems, jobs, joltage_presses))
+}
+
+pub struct Config {
+    /// Workers the machines are split between.
+    pub jobs: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self { jobs: 1 }
+    }
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+    type Input<'a> = Vec<Problem>;
+    type Config = Config;
+    const CHECKS: &'static [Check] = CHECKS;
+
+    fn with_jobs(_: Config, jobs: usize) -> Config {
+        Config { jobs }
+    }
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse(input)
+    }
+
+    fn part1(problems: &Self::Input<'_>, config: &Config) -> Result<Answer> {
+        Ok(process_part1_parallel(problems, config.jobs)?.into())
+    }
+
+    fn part2(problems: &Self::Input<'_>, config: &Config) -> Result<Option<Answer>> {
+        Ok(Some(process_part2_parallel(problems, config.jobs)?.into()))
+    }
+
+    fn stream(part: u8, reader: &mut dyn BufRead, _: &Config) -> Result<Option<Answer>> {
+        let problems = stream::lines(reader)
+            .filter(|line| !matches!(line, Ok(line) if line.text.trim().is_empty()))
+            .map(|line| line?.parse(|line| parse_machine(line, line)));
+        let presses = match part {
+            1 => process_results(problems, |problems| process_part1(problems))??,
+            2 => process_results(problems, |problems| process_part2(problems))??,
+            _ => return Ok(None),
+        };
+        Ok(Some(presses.into()))
+    }
+
+    /// Lights and joltages come from pressing random buttons, so both parts have an answer.
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let machines = (0..size.max(1)).map(|_| {
+            let lights = rng.between(3, 10) as usize;
+            let buttons: Vec<Vec<usize>> = (0..rng.between(2, lights as u64 + 3))
+                .map(|_| {
+                    let mut wired: Vec<_> = (0..lights).collect();
+                    rng.shuffle(&mut wired);
+                    wired.truncate(rng.between(1, lights as u64) as usize);
+                    wired.sort_unstable();
+                    wired
+                })
+                .collect();
+            let mut diagram = vec![false; lights];
+            let mut joltage = vec![0; lights];
+            for button in &buttons {
+                let toggled = rng.chance(1, 2);
+                let presses = rng.between(0, 9);
+                for &light in button {
+                    diagram[light] ^= toggled;
+                    joltage[light] += presses;
+                }
+            }
+            format!(
+                "[{}] {} {{{}}}\n",
+                diagram
+                    .iter()
+                    .map(|&on| if on { '#' } else { '.' })
+                    .collect::<String>(),
+                buttons
+                    .iter()
+                    .map(|button| format!("({})", button.iter().join(",")))
+                    .join(" "),
+                joltage.iter().join(","),
+            )
+        });
+        Some(machines.collect())
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::verify::assert_answers;
 
     #[test_log::test]
     fn test_part1() {
         let input = "[.###] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
 [...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
 [.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}";
-        assert_eq!(process_part1(input), 7);
-        assert_eq!(process_part1(include_str!("../input/day10.txt")), 396);
+        assert_eq!(process_part1(parse(input).unwrap()).unwrap(), 7);
+        assert_answers(10, 1, |input| process_part1(parse(input).unwrap()).unwrap());
+        assert_eq!(process_part1_bfs(&parse(input).unwrap()).unwrap(), 7);
+        assert_answers(10, 1, |input| {
+            process_part1_bfs(&parse(input).unwrap()).unwrap()
+        });
     }
 
     #[test_log::test]
@@ -182,7 +390,58 @@ mod test {
         let input = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
 [...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
 [.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}";
-        assert_eq!(process_part2(input), 33);
-        assert_eq!(process_part2(include_str!("../input/day10.txt")), 15688);
+        assert_eq!(process_part2(parse(input).unwrap()).unwrap(), 33);
+        assert_answers(10, 2, |input| process_part2(parse(input).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn test_parallel() {
+        let input = parse(
+            "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
+[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
+[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}",
+        )
+        .unwrap();
+        for jobs in [1, 2, 3, 8] {
+            assert_eq!(process_part1_parallel(&input, jobs).unwrap(), 7);
+            assert_eq!(process_part2_parallel(&input, jobs).unwrap(), 33);
+        }
+        let input = parse("[.#] (1) {0,1}\n[.#] (0) {1,0}\n[#.] (0) {1,0}").unwrap();
+        assert_eq!(
+            process_part1_parallel(&input, 3).unwrap_err().to_string(),
+            "machine #1 can't be configured"
+        );
+    }
+
+    #[test]
+    fn test_stream() {
+        let input = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
+[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
+
+[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}
+";
+        let stream =
+            |part, input: &str| Day10::stream(part, &mut input.as_bytes(), &Config::default());
+        assert_eq!(stream(1, input).unwrap(), Some(7.into()));
+        assert_eq!(stream(2, input).unwrap(), Some(33.into()));
+        let err = stream(1, "[.#] (1) (0) {1,2}\n[..#] (0,x) {1,2,3}").unwrap_err();
+        assert_eq!(err.to_string(), "2:9: unexpected `,`");
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = parse("[.##.] (3) (1,3) {3,5,4,7}\n[..#] (0,x) {1,2,3}").unwrap_err();
+        assert_eq!(err.to_string(), "2:9: unexpected `,`");
+        let err = parse("[.#] (0) (1,2) {3,5}").unwrap_err();
+        assert_eq!(err.to_string(), "1:1: button wired past 2 lights");
+    }
+
+    #[test]
+    fn test_unsolvable() {
+        let input = parse("[##] (0) (0,1) {2,1}\n[.#] (0) {1,1}").unwrap();
+        let err = process_part1(&input).unwrap_err();
+        assert_eq!(err.to_string(), "machine #1 can't be configured");
+        let err = process_part2(&input).unwrap_err();
+        assert_eq!(err.to_string(), "machine #1 can't be configured");
     }
 }
@@ -1,85 +1,256 @@
-use std::collections::HashMap;
+use std::{borrow::Cow, io::BufRead};
+
 use tracing::info;
 
-fn parse(input: &str) -> HashMap<&str, Vec<&str>> {
-    input
-        .lines()
-        .map(|line| {
-            let (name, rest) = line.split_once(':').expect("Expected `:`");
-            (name.trim(), rest.split_ascii_whitespace().collect())
-        })
-        .collect()
+use crate::{
+    answer::Answer,
+    error::{Error, Result},
+    generator::Rng,
+    graph::{Graph, Interner},
+    solution::Solution,
+    stream,
+};
+
+#[derive(Debug)]
+pub struct Devices<'a> {
+    names: Interner<Cow<'a, str>>,
+    wiring: Graph,
+    out: usize,
+}
+
+fn parse_device<'a>(input: &str, line: &'a str) -> Result<(&'a str, Vec<&'a str>)> {
+    let (name, rest) = line
+        .split_once(':')
+        .ok_or_else(|| Error::at(input, line, "expected `:`"))?;
+    let outputs: Vec<_> = rest.split_ascii_whitespace().collect();
+    if outputs.is_empty() {
+        return Err(Error::at(input, line, "device has no outputs"));
+    }
+    Ok((name.trim(), outputs))
+}
+
+/// Devices wired up a line at a time.
+struct Wiring<'a> {
+    names: Interner<Cow<'a, str>>,
+    edges: Vec<(usize, usize)>,
+    /// Whether a line names the device, `out` needs none.
+    known: Vec<bool>,
+    /// Where a device no line names yet is first used as an output.
+    unknown: Vec<Option<Error>>,
 }
 
-fn count(node: &str, devices: &HashMap<&str, Vec<&str>>) -> u64 {
-    if node == "out" {
-        1
-    } else {
-        devices
-            .get(node)
-            .unwrap_or_else(|| panic!("node {node} is missing"))
-            .iter()
-            .map(|subnode| count(subnode, devices))
-            .sum()
+impl<'a> Wiring<'a> {
+    fn new() -> Self {
+        let mut wiring = Self {
+            names: Interner::new(),
+            edges: vec![],
+            known: vec![],
+            unknown: vec![],
+        };
+        let out = wiring.id("out", Cow::Borrowed);
+        wiring.known[out] = true;
+        wiring
+    }
+
+    /// Id of `name`, interned as `key(name)` when it is new.
+    fn id<'b>(&mut self, name: &'b str, key: impl FnOnce(&'b str) -> Cow<'a, str>) -> usize {
+        self.names.get(name).unwrap_or_else(|| {
+            self.known.push(false);
+            self.unknown.push(None);
+            self.names.intern(key(name))
+        })
+    }
+
+    /// Adds the device `name` feeding `outputs`, `error` places a message at an output.
+    fn add<'b>(
+        &mut self,
+        (name, outputs): (&'b str, Vec<&'b str>),
+        key: impl Fn(&'b str) -> Cow<'a, str>,
+        error: impl Fn(&str, String) -> Error,
+    ) {
+        let from = self.id(name, &key);
+        self.known[from] = true;
+        self.unknown[from] = None;
+        for output in outputs {
+            let to = self.id(output, &key);
+            if !self.known[to] && self.unknown[to].is_none() {
+                self.unknown[to] = Some(error(output, format!("unknown device `{output}`")));
+            }
+            self.edges.push((from, to));
+        }
+    }
+
+    fn build(self) -> Result<Devices<'a>> {
+        // every output must lead somewhere so counting never hits a dead end, ids go in
+        // the order names are first seen so this is the first unknown one
+        if let Some(err) = self.unknown.into_iter().flatten().next() {
+            return Err(err);
+        }
+        let wiring = Graph::directed(self.names.len(), self.edges);
+        let out = self.names.get("out").expect("interned up front");
+        Ok(Devices {
+            names: self.names,
+            wiring,
+            out,
+        })
     }
 }
-fn process_part1(input: &str) -> u64 {
-    count("you", &parse(input))
+
+fn parse(input: &str) -> Result<Devices<'_>> {
+    let mut wiring = Wiring::new();
+    for line in input.lines() {
+        wiring.add(
+            parse_device(input, line)?,
+            Cow::Borrowed,
+            |output, message| Error::at(input, output, message),
+        );
+    }
+    wiring.build()
 }
 
-/// (total, dac, fft, both)
-type Cache<'a> = HashMap<&'a str, (u64, u64, u64, u64)>;
-
-#[tracing::instrument(skip(input))]
-fn process_part2(input: &str) -> u64 {
-    fn trace_count<'a>(
-        node: &str,
-        devices: &HashMap<&str, Vec<&'a str>>,
-        cache: &mut Cache<'a>,
-    ) -> (u64, u64, u64, u64) {
-        if node == "out" {
-            info!("out");
-            return (1, 0, 0, 0);
+/// Number of paths from `from` to `to`, visiting nodes in topological `order`.
+fn count(wiring: &Graph, order: &[usize], from: usize, to: usize) -> u64 {
+    let mut paths = vec![0; wiring.len()];
+    paths[to] = 1;
+    for &node in order.iter().rev() {
+        if node != to {
+            paths[node] = wiring
+                .neighbours(node)
+                .iter()
+                .map(|&next| paths[next])
+                .sum();
         }
+    }
+    paths[from]
+}
 
-        let (total, with_dac, with_fft, with_both) = devices
-            .get(node)
-            .unwrap_or_else(|| panic!("node {node} is missing"))
-            .iter()
-            .map(|&subnode| {
-                if let Some(cached) = cache.get(subnode) {
-                    *cached
-                } else {
-                    let value = trace_count(subnode, devices, cache);
-                    info!(subnode, ?value);
+fn check_start(node: &str, devices: &Devices) -> Result<usize> {
+    devices
+        .names
+        .get(node)
+        .ok_or_else(|| Error::puzzle(format!("node {node} is missing")))
+}
+
+fn wiring_order(devices: &Devices) -> Result<Vec<usize>> {
+    devices
+        .wiring
+        .topo_order()
+        .map_err(|_| Error::puzzle("devices are wired in a loop"))
+}
 
-                    cache.insert(subnode, value);
+pub fn process_part1(devices: &Devices) -> Result<u64> {
+    let you = check_start("you", devices)?;
+    let order = wiring_order(devices)?;
+    Ok(count(&devices.wiring, &order, you, devices.out))
+}
+
+#[tracing::instrument(skip(devices))]
+pub fn process_part2(devices: &Devices) -> Result<u64> {
+    let svr = check_start("svr", devices)?;
+    let order = wiring_order(devices)?;
+    let (Some(dac), Some(fft)) = (devices.names.get("dac"), devices.names.get("fft")) else {
+        return Ok(0);
+    };
+    let out = devices.out;
+
+    let paths = |route: [usize; 4]| -> u64 {
+        let value = route
+            .windows(2)
+            .map(|leg| count(&devices.wiring, &order, leg[0], leg[1]))
+            .product();
+        info!(?route, value);
+        value
+    };
+    // the wiring has no loops, so only one of these orders can have paths
+    Ok(paths([svr, dac, fft, out]) + paths([svr, fft, dac, out]))
+}
 
-                    value
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+    type Input<'a> = Devices<'a>;
+    type Config = ();
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse(input)
+    }
+
+    fn part1(devices: &Self::Input<'_>, _: &()) -> Result<Answer> {
+        Ok(process_part1(devices)?.into())
+    }
+
+    fn part2(devices: &Self::Input<'_>, _: &()) -> Result<Option<Answer>> {
+        Ok(Some(process_part2(devices)?.into()))
+    }
+
+    /// Keeps the names and connections of the devices but not their lines.
+    fn stream(part: u8, reader: &mut dyn BufRead, _: &()) -> Result<Option<Answer>> {
+        let mut wiring = Wiring::new();
+        for line in stream::lines(reader) {
+            let line = line?;
+            let device = parse_device(&line.text, &line.text)
+                .map_err(|err| err.shifted(line.line, line.column))?;
+            let owned = |name: &str| Cow::Owned(name.to_owned());
+            wiring.add(device, owned, |output, message| {
+                Error::at(&line.text, output, message).shifted(line.line, line.column)
+            });
+        }
+        let devices = wiring.build()?;
+        match part {
+            1 => Self::part1(&devices, &()).map(Some),
+            2 => Self::part2(&devices, &()),
+            _ => Ok(None),
+        }
+    }
+
+    /// Devices only feed ones after them, so the wiring has no loops, and about a third
+    /// of the outputs go straight `out` to keep the path counts small.
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        const NAMED: [&str; 4] = ["you", "svr", "dac", "fft"];
+        let letters = |mut n: usize| {
+            let mut name = String::new();
+            for _ in 0..3 {
+                name.push(char::from(b'a' + (n % 26) as u8));
+                n /= 26;
+            }
+            name
+        };
+        let mut names: Vec<String> = (0..26 * 26 * 26)
+            .map(letters)
+            .filter(|name| name != "out" && !NAMED.contains(&name.as_str()))
+            .collect();
+        rng.shuffle(&mut names);
+        names.truncate(size.saturating_sub(NAMED.len()));
+        names.extend(NAMED.map(String::from));
+        rng.shuffle(&mut names);
+        // the server feeds everything
+        let svr = names.iter().position(|name| name == "svr")?;
+        names.swap(0, svr);
+
+        let mut input = String::new();
+        for (i, name) in names.iter().enumerate() {
+            let mut outputs = vec![];
+            for _ in 0..rng.between(1, 2) {
+                let output = match names.len() - i - 1 {
+                    0 => "out",
+                    _ if rng.chance(1, 3) => "out",
+                    later => &names[i + 1 + rng.below(later.min(8))],
+                };
+                if !outputs.contains(&output) {
+                    outputs.push(output);
                 }
-            })
-            .reduce(|acc, v| (acc.0 + v.0, acc.1 + v.1, acc.2 + v.2, acc.3 + v.3))
-            .expect("empty children list");
-        (
-            total,
-            if node == "dac" { total } else { with_dac },
-            if node == "fft" { total } else { with_fft },
-            match (node == "dac", node == "fft") {
-                (true, true) => total,
-                (true, false) => with_fft,
-                (false, true) => with_dac,
-                (false, false) => with_both,
-            },
-        )
-    }
-
-    let mut cache: Cache = HashMap::new();
-    trace_count("svr", &parse(input), &mut cache).3
+            }
+            input += &format!("{name}: {}\n", outputs.join(" "));
+        }
+        Some(input)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::verify::assert_answers;
 
     #[test]
     fn test_part1() {
@@ -93,8 +264,10 @@ fff: out
 ggg: out
 hhh: ccc fff iii
 iii: out";
-        assert_eq!(process_part1(input), 5);
-        assert_eq!(process_part1(include_str!("../input/day11.txt")), 494);
+        assert_eq!(process_part1(&parse(input).unwrap()).unwrap(), 5);
+        assert_answers(11, 1, |input| {
+            process_part1(&parse(input).unwrap()).unwrap()
+        });
     }
 
     #[test_log::test]
@@ -112,10 +285,44 @@ dac: fff
 fff: ggg hhh
 ggg: out
 hhh: out";
-        assert_eq!(process_part2(input), 2);
+        assert_eq!(process_part2(&parse(input).unwrap()).unwrap(), 2);
+        assert_answers(11, 2, |input| {
+            process_part2(&parse(input).unwrap()).unwrap()
+        });
+    }
+
+    #[test]
+    fn test_stream() {
+        let input = "aaa: you hhh\nyou: bbb ccc\nbbb: ddd eee\nccc: ddd eee fff\nddd: ggg
+eee: out\nfff: out\nggg: out\nhhh: ccc fff iii\niii: out\n";
+        let stream = |part, input: &str| Day11::stream(part, &mut input.as_bytes(), &());
+        assert_eq!(stream(1, input).unwrap(), Some(5.into()));
+        assert_eq!(
+            stream(1, "you: out\naaa out").unwrap_err().to_string(),
+            "2:1: expected `:`"
+        );
+        assert_eq!(
+            stream(1, "aaa: out\nyou: aaa bbb").unwrap_err().to_string(),
+            "2:10: unknown device `bbb`"
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            parse("you: out\naaa out").unwrap_err().to_string(),
+            "2:1: expected `:`"
+        );
+        assert_eq!(
+            parse("you: aaa bbb\naaa: out").unwrap_err().to_string(),
+            "1:10: unknown device `bbb`"
+        );
+        assert!(process_part2(&parse("you: out").unwrap()).is_err());
         assert_eq!(
-            process_part2(include_str!("../input/day11.txt")),
-            296006754704850
+            process_part1(&parse("you: aaa\naaa: you out").unwrap())
+                .unwrap_err()
+                .to_string(),
+            "devices are wired in a loop"
         );
     }
 }
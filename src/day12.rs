@@ -1,26 +1,35 @@
 use glam::{USizeVec2, usizevec2};
-use std::{borrow::Cow, iter::repeat_n};
-
+use itertools::Itertools;
 use nom::{
     IResult, Parser, branch,
     bytes::tag,
     character::complete::{self, line_ending, space1},
-    combinator::{all_consuming, eof, opt},
+    combinator::{all_consuming, eof, map_opt, opt},
     multi::{many1, separated_list1},
     sequence::{separated_pair, terminated},
 };
 use tracing::info;
 
+use crate::{
+    answer::Answer,
+    error::{Error, Result},
+    generator::Rng,
+    geometry::Rect,
+    grid::Grid,
+    parallel,
+    solution::Solution,
+};
+
 #[derive(Debug, Clone)]
-struct Present {
+pub struct Present {
     num: usize,
     size: usize,
-    rows: Vec<Vec<bool>>,
+    shape: Grid<bool>,
 }
 
 #[derive(Debug)]
-struct CristmassTree {
-    size: (usize, usize),
+pub struct CristmassTree {
+    size: USizeVec2,
     needed: Vec<usize>,
 }
 
@@ -30,20 +39,23 @@ fn present(input: &str) -> IResult<&str, Present> {
             complete::usize,
             (complete::char(':'), complete::line_ending),
         ),
-        terminated(
-            separated_list1(
-                complete::line_ending,
-                many1(branch::alt((
-                    complete::char('#').map(|_| true),
-                    complete::char('.').map(|_| false),
-                ))),
+        map_opt(
+            terminated(
+                separated_list1(
+                    complete::line_ending,
+                    many1(branch::alt((
+                        complete::char('#').map(|_| true),
+                        complete::char('.').map(|_| false),
+                    ))),
+                ),
+                line_ending,
             ),
-            line_ending,
+            Grid::from_rows,
         ),
     )
-        .map(|(num, rows)| {
-            let size = rows.iter().flatten().filter(|&v| *v).count();
-            Present { num, size, rows }
+        .map(|(num, shape)| {
+            let size = shape.cells().filter(|&v| *v).count();
+            Present { num, size, shape }
         })
         .parse(input)
 }
@@ -57,8 +69,8 @@ fn cristmass_tree(input: &str) -> IResult<&str, CristmassTree> {
         ),
         opt(eof),
     )
-    .map(|(size, presents)| CristmassTree {
-        size,
+    .map(|((width, length), presents)| CristmassTree {
+        size: usizevec2(width, length),
         needed: presents,
     })
     .parse(input)
@@ -76,74 +88,102 @@ fn parse(input: &str) -> IResult<&str, (Vec<Present>, Vec<CristmassTree>)> {
     .parse(input)
 }
 
+fn parse_input(input: &str) -> Result<(Vec<Present>, Vec<CristmassTree>)> {
+    let (_, (presents, trees)) = parse(input).map_err(|err| Error::nom(input, err))?;
+
+    for (idx, present) in presents.iter().enumerate() {
+        let header = format!("{}:", present.num);
+        let line = input.lines().find(|line| *line == header).unwrap_or(input);
+        if present.num != idx {
+            return Err(Error::at(input, line, format!("expected present {idx}")));
+        }
+        if present.shape.size() != usizevec2(3, 3) {
+            return Err(Error::at(input, line, "expected a 3x3 present"));
+        }
+    }
+    let tree_lines = input.lines().rev().filter(|line| !line.is_empty());
+    for (tree, line) in trees.iter().rev().zip(tree_lines) {
+        if tree.needed.len() > presents.len() {
+            return Err(Error::at(
+                input,
+                line,
+                format!("only {} presents are known", presents.len()),
+            ));
+        }
+    }
+    Ok((presents, trees))
+}
+
 impl Present {
     fn rotate(self, rotation: u8) -> Self {
         if rotation == 0 {
             return self;
         };
         let mut out = self.clone();
-        for i in 0..3usize {
-            for j in 0..3usize {
-                out.rows[j][2 - i] = self.rows[i][j]
-            }
+        for pos in self.shape.positions() {
+            out.shape[usizevec2(2 - pos.y, pos.x)] = self.shape[pos]
         }
         out.rotate(rotation - 1)
     }
 }
 
-type Field = Vec<Vec<bool>>;
-
-fn pack(field: &Field, present: &Present, offset: USizeVec2) -> Option<Field> {
-    let mut out = Cow::from(field);
+type Field = Grid<bool>;
 
-    for i in 0..3 {
-        for j in 0..3 {
-            if *out.get(offset.y + i)?.get(offset.x + j)? && present.rows[i][j] {
-                // already occupied
-                return None;
-            }
-            out.to_mut()[offset.y + i][offset.x + j] = present.rows[i][j]
-        }
+/// Fills the cells of `present` at `offset` unless one of them is outside `field` or
+/// already filled, the cells its shape leaves empty are left as they are.
+fn pack(field: &mut Field, present: &Present, offset: USizeVec2) -> bool {
+    let cells = || present.shape.positions().filter(|&pos| present.shape[pos]);
+    if !cells().all(|pos| field.get(offset + pos) == Some(&false)) {
+        return false;
     }
-    Some(out.into())
+    for pos in cells() {
+        field[offset + pos] = true;
+    }
+    true
 }
 
-fn stuff_tree(field: &Field, needed: &[usize], presents: &Vec<Present>) -> Option<Field> {
-    if let Some(present_id) = needed.iter().position(|amt| *amt > 0) {
-        // info!(placing = present_id, from = ?needed);
-        let mut new_needed = needed.to_owned();
-        new_needed[present_id] -= 1;
-
-        for r in 0..=3 {
-            let present = presents[present_id].clone().rotate(r);
-            for y in 0..(field.len() - 2) {
-                for x in 0..(field[y].len() - 2) {
-                    // info!(present.num, x, y, r);
+/// Empties the cells [`pack`] filled for `present` at `offset`.
+fn unpack(field: &mut Field, present: &Present, offset: USizeVec2) {
+    for pos in present.shape.positions().filter(|&pos| present.shape[pos]) {
+        field[offset + pos] = false;
+    }
+}
 
-                    // try to pack current present
-                    if let Some(new_field) = pack(field, &present, usizevec2(x, y)) {
-                        // try to pack other presents
-                        if let Some(out) = stuff_tree(&new_field, &new_needed, presents) {
-                            info!("fit {present_id} {needed:?} @{x},{y} r={r}!");
-                            return Some(out);
-                        }
+/// Whether the `needed` presents fit in what `field` has left, which it is back to
+/// when this returns.
+fn stuff_tree(field: &mut Field, needed: &[usize], presents: &Vec<Present>) -> bool {
+    let Some(present_id) = needed.iter().position(|amt| *amt > 0) else {
+        // all placed already
+        return true;
+    };
+    let mut new_needed = needed.to_owned();
+    new_needed[present_id] -= 1;
 
-                        // failed to pack other presents, lets move current present and try again
-                    }
+    for r in 0..=3 {
+        let present = presents[present_id].clone().rotate(r);
+        let Some(last) = field.size().checked_sub(present.shape.size()) else {
+            continue;
+        };
+        for offset in Rect::from_corners(USizeVec2::ZERO, last).points() {
+            // try to pack current present
+            if pack(field, &present, offset) {
+                // try to pack other presents
+                let fit = stuff_tree(field, &new_needed, presents);
+                unpack(field, &present, offset);
+                if fit {
+                    info!("fit {present_id} {needed:?} @{offset} r={r}!");
+                    return true;
                 }
+                // failed to pack other presents, lets move current present and try again
             }
         }
-        // failed at each possible position. Not possible to fit all presents
-        // info!("couldn't fit {present_id}, {:?}", needed);
-        return None;
     }
-
-    // all placed already
-    Some(field.to_owned())
+    // failed at each possible position. Not possible to fit all presents
+    false
 }
 
 fn pre_check(tree: &CristmassTree, presents: &[Present]) -> bool {
-    let space_available = tree.size.0 * tree.size.1;
+    let space_available = tree.size.element_product();
     let space_needed: usize = tree
         .needed
         .iter()
@@ -154,37 +194,109 @@ fn pre_check(tree: &CristmassTree, presents: &[Present]) -> bool {
     space_needed < space_available
 }
 
-fn process(input: &str) -> usize {
-    let (_, (presents, trees)) = parse(input).expect("Failed to parse input");
+/// Whether the presents `tree` needs fit under it, `idx` is only for the logs.
+fn fits(idx: usize, tree: &CristmassTree, presents: &Vec<Present>) -> bool {
+    let mut field = Grid::new(tree.size.x, tree.size.y, false);
+    if pre_check(tree, presents) {
+        // this is not needed for actual input
+        if stuff_tree(&mut field, &tree.needed, presents) {
+            info!("tree #{idx}: Success!");
+            true
+        } else {
+            info!("tree #{idx}: Failure!");
+            false
+        }
+    } else {
+        info!("tree #{idx}: Precheck failure!");
+        false
+    }
+}
 
-    trees
-        .iter()
-        .enumerate()
-        // .take(10)
-        .filter(|&(idx, tree)| {
-            let (width, length) = tree.size;
-            let field = Vec::from_iter(repeat_n(Vec::from_iter(repeat_n(false, width)), length));
-            if pre_check(tree, &presents) {
-                // this is not needed for actual input
-                if stuff_tree(&field, &tree.needed, &presents).is_some() {
-                    info!("tree #{idx}: Success!");
-                    true
-                } else {
-                    info!("tree #{idx}: Failure!");
-                    false
+/// Number of trees that fit their presents, checked by `jobs` workers.
+pub fn process((presents, trees): &(Vec<Present>, Vec<CristmassTree>), jobs: usize) -> usize {
+    parallel::map(trees, jobs, |idx, tree| fits(idx, tree, presents))
+        .into_iter()
+        .filter(|&fits| fits)
+        .count()
+}
+
+pub struct Config {
+    /// Workers the trees are split between.
+    pub jobs: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self { jobs: 1 }
+    }
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+    type Input<'a> = (Vec<Present>, Vec<CristmassTree>);
+    type Config = Config;
+
+    fn with_jobs(_: Config, jobs: usize) -> Config {
+        Config { jobs }
+    }
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input<'_>, config: &Config) -> Result<Answer> {
+        Ok(process(input, config.jobs).into())
+    }
+
+    /// Trees either have a 3x3 slot to spare for every present or too little area for
+    /// them, the kinds the puzzle input has, so the search never has to backtrack far.
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        let mut input = String::new();
+        let mut cells = vec![];
+        for num in 0..6 {
+            let shape: Vec<bool> = loop {
+                let shape: Vec<_> = (0..9).map(|_| rng.chance(2, 3)).collect();
+                if shape.iter().filter(|&&cell| cell).count() >= 5 {
+                    break shape;
+                }
+            };
+            cells.push(shape.iter().filter(|&&cell| cell).count());
+            input += &format!("{num}:\n");
+            for row in shape.chunks(3) {
+                input.extend(row.iter().map(|&cell| if cell { '#' } else { '.' }));
+                input.push('\n');
+            }
+            input.push('\n');
+        }
+        for _ in 0..size.max(1) {
+            let (width, length) = (rng.between(6, 50), rng.between(6, 50));
+            let mut needed = [0_u64; 6];
+            if rng.chance(1, 2) {
+                let slots = (width / 3) * (length / 3);
+                for _ in 0..rng.between(1, slots * 3 / 4) {
+                    needed[rng.below(6)] += 1;
                 }
             } else {
-                info!("tree #{idx}: Precheck failure!");
-                false
+                let mut space = 0;
+                while space < width * length {
+                    let present = rng.below(6);
+                    needed[present] += 1;
+                    space += cells[present] as u64;
+                }
             }
-        })
-        .count()
+            input += &format!("{width}x{length}: {}\n", needed.iter().join(" "));
+        }
+        Some(input)
+    }
 }
 
 #[cfg(test)]
 mod test {
 
     use super::*;
+    use crate::verify::assert_answers;
 
     #[test_log::test]
     fn test_part1() {
@@ -222,10 +334,37 @@ mod test {
 12x5: 1 0 1 0 2 2
 12x5: 1 0 1 0 3 2";
 
-        assert_eq!(process(input), 2);
-        assert_eq!(process(include_str!("../input/day12.txt")), 505);
+        let input = parse_input(input).unwrap();
+        for jobs in [1, 2, 3] {
+            assert_eq!(process(&input, jobs), 2);
+        }
+        assert_answers(12, 1, |input| process(&parse_input(input).unwrap(), 1));
     }
-}
 
-// 60
-//
+    #[test]
+    fn test_pack() {
+        let (presents, _) = parse_input("0:\n###\n#..\n###\n\n3x3: 1").unwrap();
+        let mut field = Grid::new(4, 3, false);
+        field[usizevec2(1, 1)] = true;
+        // the gap of the shape goes over the filled cell and keeps it
+        assert!(pack(&mut field, &presents[0], USizeVec2::ZERO));
+        assert_eq!(field.cells().filter(|&&filled| filled).count(), 8);
+        assert!(!pack(&mut field, &presents[0], usizevec2(1, 0)));
+        unpack(&mut field, &presents[0], USizeVec2::ZERO);
+        assert_eq!(field.cells().filter(|&&filled| filled).count(), 1);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = parse_input("0:\n###\n#x.\n###\n\n4x4: 1").unwrap_err();
+        assert_eq!(err.to_string(), "3:2: expected a line break, found `x`");
+        let err = parse_input("0:\n###\n##\n###\n\n4x4: 1").unwrap_err();
+        assert_eq!(err.to_string(), "2:1: unexpected `#`");
+        let err = parse_input("0:\n###\n###\n###\n\n4x4: 1\ngarbage").unwrap_err();
+        assert_eq!(err.to_string(), "7:1: expected end of input, found `g`");
+        let err = parse_input("0:\n##\n##\n\n4x4: 1").unwrap_err();
+        assert_eq!(err.to_string(), "1:1: expected a 3x3 present");
+        let err = parse_input("0:\n###\n###\n###\n\n4x4: 1\n5x5: 1 1").unwrap_err();
+        assert_eq!(err.to_string(), "7:1: only 1 presents are known");
+    }
+}
@@ -0,0 +1,121 @@
+use std::fmt;
+
+use crate::{answer::Answer, error::Result, generator, solution};
+
+/// A solver checked against a slower, obviously right reference on generated inputs.
+pub struct Check {
+    /// `dayNN::<what>`, checks are picked by a prefix of it.
+    pub name: &'static str,
+    pub day: u8,
+    /// Generator size that keeps the reference quick.
+    pub size: usize,
+    pub reference: fn(&str) -> Result<Answer>,
+    pub optimized: fn(&str) -> Result<Answer>,
+}
+
+/// Answer or error message of one solver, errors agree when their messages do.
+pub type Outcome = std::result::Result<Answer, String>;
+
+fn outcome(solve: fn(&str) -> Result<Answer>, input: &str) -> Outcome {
+    solve(input).map_err(|err| err.to_string())
+}
+
+/// First generated input the two solvers of a check disagree on.
+#[derive(Debug, Clone)]
+pub struct Mismatch {
+    pub seed: u64,
+    pub input: String,
+    pub reference: Outcome,
+    pub optimized: Outcome,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |outcome: &Outcome| match outcome {
+            Ok(answer) => answer.to_string(),
+            Err(err) => format!("error `{err}`"),
+        };
+        write!(
+            f,
+            "seed {}: reference {}, optimized {}",
+            self.seed,
+            show(&self.reference),
+            show(&self.optimized)
+        )
+    }
+}
+
+impl Check {
+    /// Runs both solvers on `input`, `None` if they agree.
+    pub fn compare(&self, input: &str) -> Option<(Outcome, Outcome)> {
+        let reference = outcome(self.reference, input);
+        let optimized = outcome(self.optimized, input);
+        (reference != optimized).then_some((reference, optimized))
+    }
+
+    /// Tries every seed in `seeds` with `size` (the check's own when `None`), stopping
+    /// at the first disagreement.
+    pub fn run(
+        &self,
+        seeds: impl IntoIterator<Item = u64>,
+        size: Option<usize>,
+    ) -> Option<Mismatch> {
+        seeds.into_iter().find_map(|seed| {
+            let input = generator::generate(self.day, size.unwrap_or(self.size), seed)?;
+            let (reference, optimized) = self.compare(&input)?;
+            Some(Mismatch {
+                seed,
+                input,
+                reference,
+                optimized,
+            })
+        })
+    }
+}
+
+/// Every check of every day, in day order.
+pub fn checks() -> impl Iterator<Item = &'static Check> {
+    solution::DAYS.iter().flat_map(|day| day.checks())
+}
+
+/// The check named exactly `name`.
+pub fn find(name: &str) -> Option<&'static Check> {
+    checks().find(|check| check.name == name)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_checks() {
+        let names: Vec<_> = checks().map(|check| check.name).collect();
+        assert_eq!(names[..2], ["day01::part2", "day02::part1"]);
+        for check in checks() {
+            assert!(check.name.starts_with(&format!("day{:02}::", check.day)));
+            if let Some(mismatch) = check.run(0..20, None) {
+                panic!("{} {mismatch}\n{}", check.name, mismatch.input);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mismatch() {
+        let check = Check {
+            name: "day01::broken",
+            day: 1,
+            size: 10,
+            reference: |input| Ok(input.lines().count().into()),
+            optimized: |input| match input.lines().count() {
+                // wrong once there are more than ten lines
+                n if n > 10 => Ok((n - 1).into()),
+                n => Ok(n.into()),
+            },
+        };
+        assert!(check.run(0..5, None).is_none());
+        let mismatch = check.run(3..5, Some(12)).unwrap();
+        assert_eq!(mismatch.seed, 3);
+        assert_eq!(mismatch.input.lines().count(), 12);
+        assert_eq!(mismatch.to_string(), "seed 3: reference 12, optimized 11");
+    }
+}
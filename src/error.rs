@@ -0,0 +1,185 @@
+use std::{fmt, io, str::FromStr};
+
+use nom::error::ErrorKind;
+
+#[derive(Debug)]
+pub enum Error {
+    /// Malformed input at a 1-based line and column.
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// Input is well-formed but the puzzle can't be solved for it.
+    Puzzle(String),
+    /// Talking to the puzzle site failed.
+    Http(String),
+    /// An answer was held back, it is known to be wrong or it is too early.
+    Refused(String),
+    Io(io::Error),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+    /// Parse error at byte `offset` of `input`.
+    pub fn at_offset(input: &str, offset: usize, message: impl Into<String>) -> Self {
+        let before = &input[..offset.min(input.len())];
+        let line_start = before.rfind('\n').map_or(0, |pos| pos + 1);
+        Error::Parse {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            message: message.into(),
+        }
+    }
+
+    /// Parse error at the start of `fragment`, which must be a slice of `input`.
+    pub fn at(input: &str, fragment: &str, message: impl Into<String>) -> Self {
+        let offset = (fragment.as_ptr() as usize).wrapping_sub(input.as_ptr() as usize);
+        debug_assert!(offset <= input.len(), "fragment is not part of input");
+        Self::at_offset(input, offset, message)
+    }
+
+    /// Moves a parse error in a fragment that starts at `line` and `column` of a larger
+    /// input to its place in that input. Other errors are kept as they are.
+    pub fn shifted(self, line: usize, column: usize) -> Self {
+        match self {
+            Error::Parse {
+                line: 1,
+                column: c,
+                message,
+            } => Error::Parse {
+                line,
+                column: column + c - 1,
+                message,
+            },
+            Error::Parse {
+                line: l,
+                column,
+                message,
+            } => Error::Parse {
+                line: line + l - 1,
+                column,
+                message,
+            },
+            err => err,
+        }
+    }
+
+    /// Converts a nom failure on `input` into a positioned parse error.
+    pub fn nom(input: &str, err: nom::Err<nom::error::Error<&str>>) -> Self {
+        match err {
+            nom::Err::Incomplete(_) => {
+                Self::at_offset(input, input.len(), "unexpected end of input")
+            }
+            nom::Err::Error(err) | nom::Err::Failure(err) => {
+                let found = match err.input.chars().next() {
+                    Some(c) => format!("`{}`", c.escape_debug()),
+                    None => "end of input".to_string(),
+                };
+                let message = match expected(err.code) {
+                    Some(what) => format!("expected {what}, found {found}"),
+                    None => format!("unexpected {found}"),
+                };
+                Self::at(input, err.input, message)
+            }
+        }
+    }
+
+    pub fn puzzle(message: impl Into<String>) -> Self {
+        Error::Puzzle(message.into())
+    }
+}
+
+/// What a nom parser that failed with `kind` was looking for, if that says anything
+/// to someone who hasn't read the parser.
+fn expected(kind: ErrorKind) -> Option<&'static str> {
+    match kind {
+        ErrorKind::Digit => Some("a number"),
+        ErrorKind::CrLf => Some("a line break"),
+        ErrorKind::Space | ErrorKind::MultiSpace => Some("a space"),
+        ErrorKind::Alpha => Some("a letter"),
+        ErrorKind::Eof => Some("end of input"),
+        _ => None,
+    }
+}
+
+/// Parses `token`, a slice of `input`, reporting its position on failure.
+pub fn parse_at<T: FromStr>(input: &str, token: &str, what: &str) -> Result<T> {
+    token
+        .parse()
+        .map_err(|_| Error::at(input, token, format!("expected {what}, found `{token}`")))
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse {
+                line,
+                column,
+                message,
+            } => write!(f, "{line}:{column}: {message}"),
+            Error::Puzzle(message) | Error::Http(message) | Error::Refused(message) => {
+                write!(f, "{message}")
+            }
+            Error::Io(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(0, 1, 1)]
+    #[case(2, 1, 3)]
+    #[case(4, 2, 1)]
+    #[case(9, 3, 1)]
+    #[case(10, 3, 2)]
+    #[case(100, 3, 3)]
+    fn test_position(#[case] offset: usize, #[case] line: usize, #[case] column: usize) {
+        let input = "abc\ndefg\nhi";
+        let Error::Parse {
+            line: l, column: c, ..
+        } = Error::at_offset(input, offset, "")
+        else {
+            panic!("expected parse error");
+        };
+        assert_eq!((l, c), (line, column));
+    }
+
+    #[test]
+    fn test_shifted() {
+        let err = Error::at_offset("ab\ncd", 1, "x").shifted(3, 5);
+        assert_eq!(err.to_string(), "3:6: x");
+        let err = Error::at_offset("ab\ncd", 4, "x").shifted(3, 5);
+        assert_eq!(err.to_string(), "4:2: x");
+        assert_eq!(Error::puzzle("x").shifted(3, 5).to_string(), "x");
+    }
+
+    #[test]
+    fn test_parse_at() {
+        let input = "1,2\n3,x4";
+        let token = &input[6..];
+        let err = parse_at::<u32>(input, token, "number").unwrap_err();
+        assert_eq!(err.to_string(), "2:3: expected number, found `x4`");
+        assert_eq!(parse_at::<u32>(input, &input[4..5], "number").unwrap(), 3);
+    }
+}
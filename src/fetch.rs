@@ -0,0 +1,362 @@
+use std::{
+    collections::HashMap,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use tracing::info;
+
+use crate::{
+    error::{Error, Result, parse_at},
+    input::{self, DEFAULT_PROFILE, Profile},
+};
+
+/// Config file with the session tokens, kept out of git.
+pub const CONFIG_FILE: &str = "aoc2025.conf";
+
+/// File in the input directory remembering when the last request was made.
+const STAMP_FILE: &str = ".last-fetch";
+
+const USER_AGENT: &str = "github.com/spabramov/aoc2025 by spabramov";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    /// Inputs are fetched from `<base_url>/day/<day>/input`.
+    pub base_url: String,
+    /// Session cookie per profile name.
+    pub sessions: HashMap<String, String>,
+    /// Shortest pause between two requests.
+    pub min_interval: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            base_url: "https://adventofcode.com/2025".to_string(),
+            sessions: HashMap::new(),
+            min_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+impl Config {
+    /// Parses `key = value` lines, skipping blanks and `#` comments.
+    ///
+    /// Keys are `base_url`, `min_interval` in seconds, `session` for the
+    /// default profile and `session.<profile>` for the others.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut config = Self::default();
+        for line in text.lines() {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| Error::at(text, line, "expected `<key> = <value>`"))?;
+            let (key, value) = (key.trim(), value.trim());
+            match key.split_once('.') {
+                None if key == "base_url" => {
+                    config.base_url = value.trim_end_matches('/').to_string()
+                }
+                None if key == "min_interval" => {
+                    let secs: f64 = parse_at(text, value, "seconds")?;
+                    config.min_interval = Duration::try_from_secs_f64(secs)
+                        .map_err(|_| Error::at(text, value, "expected seconds"))?;
+                }
+                None if key == "session" => {
+                    config
+                        .sessions
+                        .insert(DEFAULT_PROFILE.to_string(), value.to_string());
+                }
+                Some(("session", profile)) => {
+                    config
+                        .sessions
+                        .insert(profile.to_string(), value.to_string());
+                }
+                _ => return Err(Error::at(text, key, format!("unknown key `{key}`"))),
+            }
+        }
+        Ok(config)
+    }
+
+    /// Reads `path` if it exists, then applies the `AOC_SESSION` and
+    /// `AOC_BASE_URL` environment overrides.
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut config = match input::read(path)? {
+            Some(text) => Self::parse(&text)?,
+            None => Self::default(),
+        };
+        if let Ok(session) = std::env::var("AOC_SESSION") {
+            config.sessions.insert(DEFAULT_PROFILE.to_string(), session);
+        }
+        if let Ok(base_url) = std::env::var("AOC_BASE_URL") {
+            config.base_url = base_url.trim_end_matches('/').to_string();
+        }
+        Ok(config)
+    }
+}
+
+/// Downloads inputs into their profile directory unless they are there already.
+#[derive(Debug)]
+pub struct Fetcher {
+    config: Config,
+    stamp: PathBuf,
+}
+
+impl Fetcher {
+    /// Fetcher pacing its requests with a stamp file in the input `root`.
+    pub fn new(config: Config, root: &Path) -> Self {
+        Self {
+            config,
+            stamp: root.join(STAMP_FILE),
+        }
+    }
+
+    /// Input of `day` for `profile`, downloading it only when it is not cached.
+    pub fn fetch(&self, profile: &Profile, day: u8) -> Result<String> {
+        if let Some(input) = profile.load(day)? {
+            return Ok(input);
+        }
+        let input = self.request(profile, &format!("day/{day}/input"), &[])?;
+        if input.is_empty() {
+            return Err(Error::Http(format!("day {day}: empty input")));
+        }
+        std::fs::create_dir_all(&profile.dir)?;
+        std::fs::write(profile.day_path(day), &input)?;
+        Ok(input)
+    }
+
+    /// Requests `path` under the base URL with the session of `profile`.
+    ///
+    /// It is a GET, or a POST of `form` when that is not empty.
+    pub(crate) fn request(
+        &self,
+        profile: &Profile,
+        path: &str,
+        form: &[(&str, &str)],
+    ) -> Result<String> {
+        let session = self.config.sessions.get(&profile.name).ok_or_else(|| {
+            Error::Http(format!(
+                "no session for profile `{}` in {CONFIG_FILE}",
+                profile.name
+            ))
+        })?;
+
+        self.wait()?;
+        let url = format!("{}/{path}", self.config.base_url);
+        info!(url, profile = profile.name, "requesting");
+        let response = curl(&url, session, form);
+        std::fs::write(&self.stamp, now()?.as_millis().to_string())?;
+        response
+    }
+
+    /// Sleeps until `min_interval` has passed since the last request.
+    fn wait(&self) -> Result<()> {
+        let Some(stamp) = input::read(&self.stamp)? else {
+            return Ok(());
+        };
+        let last = Duration::from_millis(stamp.trim().parse().unwrap_or_default());
+        let next = last + self.config.min_interval;
+        if let Some(pause) = next.checked_sub(now()?) {
+            info!(?pause, "waiting before the next request");
+            std::thread::sleep(pause);
+        }
+        Ok(())
+    }
+}
+
+/// Time since the Unix epoch.
+pub(crate) fn now() -> Result<Duration> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|err| Error::Http(err.to_string()))
+}
+
+/// Runs curl on `url`, which takes the session on stdin to keep it out of `ps`.
+fn curl(url: &str, session: &str, form: &[(&str, &str)]) -> Result<String> {
+    let mut command = Command::new("curl");
+    command.args(["--silent", "--show-error", "--fail", "--config", "-"]);
+    for (key, value) in form {
+        command
+            .arg("--data-urlencode")
+            .arg(format!("{key}={value}"));
+    }
+    let mut curl = command
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| Error::Http(format!("failed to run curl: {err}")))?;
+    if let Some(mut stdin) = curl.stdin.take() {
+        write!(
+            stdin,
+            "cookie = \"session={session}\"\nuser-agent = \"{USER_AGENT}\"\n"
+        )?;
+    }
+    let output = curl.wait_with_output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::Http(format!("{url}: {}", stderr.trim())));
+    }
+    String::from_utf8(output.stdout)
+        .map_err(|_| Error::Http(format!("{url}: response is not UTF-8")))
+}
+
+/// Local stand-in for the puzzle site.
+#[cfg(test)]
+pub(crate) mod mock {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+    };
+
+    pub(crate) struct Request {
+        pub path: String,
+        pub headers: Vec<String>,
+        pub body: String,
+    }
+
+    impl Request {
+        pub fn has_session(&self, session: &str) -> bool {
+            self.headers
+                .iter()
+                .any(|header| *header == format!("Cookie: session={session}"))
+        }
+    }
+
+    /// Serves `handler` responses at the returned base URL, counting requests.
+    pub(crate) fn serve(
+        handler: impl Fn(&Request) -> (&'static str, String) + Send + 'static,
+    ) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/2025", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                counter.fetch_add(1, Ordering::SeqCst);
+                let mut reader = BufReader::new(&stream);
+                let mut lines = vec![];
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim_end().to_string();
+                    if line.is_empty() {
+                        break;
+                    }
+                    lines.push(line);
+                }
+                let length = lines
+                    .iter()
+                    .find_map(|line| line.strip_prefix("Content-Length: "))
+                    .map_or(0, |length| length.parse().unwrap());
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                let request = Request {
+                    path: lines[0].split(' ').nth(1).unwrap_or_default().to_string(),
+                    headers: lines[1..].to_vec(),
+                    body: String::from_utf8(body).unwrap(),
+                };
+                let (status, body) = handler(&request);
+                write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+        });
+        (url, requests)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{sync::atomic::Ordering, time::Instant};
+
+    use super::{mock::serve, *};
+    use crate::input::Scratch;
+
+    /// Serves `/day/<n>/input` to requests with the `abc` session.
+    fn serve_inputs() -> (String, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+        serve(|request| match request.path.strip_prefix("/2025/day/") {
+            Some(rest) if request.has_session("abc") => (
+                "200 OK",
+                format!("input of day {}\n", rest.trim_end_matches("/input")),
+            ),
+            _ => ("400 Bad Request", String::new()),
+        })
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let config = Config::parse(
+            "# tokens\nsession = abc\nsession.alice = def\nbase_url = http://localhost/2025/\nmin_interval = 0.5\n",
+        )
+        .unwrap();
+        assert_eq!(config.base_url, "http://localhost/2025");
+        assert_eq!(config.sessions["default"], "abc");
+        assert_eq!(config.sessions["alice"], "def");
+        assert_eq!(config.min_interval, Duration::from_millis(500));
+
+        let err = Config::parse("session = abc\ntoken = def").unwrap_err();
+        assert_eq!(err.to_string(), "2:1: unknown key `token`");
+        let err = Config::parse("min_interval = soon").unwrap_err();
+        assert_eq!(err.to_string(), "1:16: expected seconds, found `soon`");
+    }
+
+    #[test]
+    fn test_fetch_caches() {
+        let (base_url, requests) = serve_inputs();
+        let root = Scratch::new("fetch");
+        let config = Config {
+            base_url,
+            sessions: HashMap::from([("default".to_string(), "abc".to_string())]),
+            min_interval: Duration::ZERO,
+        };
+        let fetcher = Fetcher::new(config, &root);
+        let profile = Profile::new(&root, DEFAULT_PROFILE);
+
+        assert_eq!(fetcher.fetch(&profile, 3).unwrap(), "input of day 3\n");
+        assert_eq!(fetcher.fetch(&profile, 3).unwrap(), "input of day 3\n");
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            std::fs::read_to_string(root.join("day03.txt")).unwrap(),
+            "input of day 3\n"
+        );
+
+        let err = fetcher.fetch(&Profile::new(&root, "alice"), 3).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "no session for profile `alice` in aoc2025.conf"
+        );
+    }
+
+    #[test]
+    fn test_fetch_errors_and_interval() {
+        let (base_url, requests) = serve_inputs();
+        let root = Scratch::new("fetch-interval");
+        let config = Config {
+            base_url,
+            sessions: HashMap::from([("default".to_string(), "wrong".to_string())]),
+            min_interval: Duration::from_millis(300),
+        };
+        let fetcher = Fetcher::new(config, &root);
+        let profile = Profile::new(&root, DEFAULT_PROFILE);
+
+        let start = Instant::now();
+        assert!(fetcher.fetch(&profile, 1).is_err());
+        assert!(fetcher.fetch(&profile, 2).is_err());
+        assert!(start.elapsed() >= Duration::from_millis(300));
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        assert!(!root.join("day01.txt").exists());
+    }
+}
@@ -0,0 +1,136 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tracing::{Subscriber, span};
+use tracing_subscriber::{Layer, layer::Context, registry::LookupSpan};
+
+/// Time spent in each stack of spans, not counting the spans below it.
+#[derive(Debug, Clone, Default)]
+pub struct Folded(Arc<Mutex<BTreeMap<String, Duration>>>);
+
+impl Folded {
+    /// Layer adding the time spent in spans to this profile.
+    pub fn layer(&self) -> FoldedLayer {
+        FoldedLayer(self.clone())
+    }
+
+    /// Writes `<span>;<span>;... <microseconds>` lines, the folded stacks flamegraph tools read.
+    pub fn write(&self, mut out: impl Write) -> io::Result<()> {
+        let stacks = self.0.lock().expect("no panics while holding the lock");
+        for (stack, time) in stacks.iter() {
+            writeln!(out, "{stack} {}", time.as_micros())?;
+        }
+        Ok(())
+    }
+}
+
+/// See [`Folded::layer`].
+pub struct FoldedLayer(Folded);
+
+/// Kept in the extensions of every span.
+struct Frame {
+    stack: String,
+    entered: Option<Instant>,
+    /// Time spent in child spans since the span was entered.
+    children: Duration,
+}
+
+impl<S> Layer<S> for FoldedLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, _attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let span = ctx.span(id).expect("span is new");
+        let name = format!("{}::{}", span.metadata().target(), span.name());
+        let stack = match span
+            .parent()
+            .and_then(|parent| Some(parent.extensions().get::<Frame>()?.stack.clone()))
+        {
+            Some(parent) => format!("{parent};{name}"),
+            None => name,
+        };
+        span.extensions_mut().insert(Frame {
+            stack,
+            entered: None,
+            children: Duration::ZERO,
+        });
+    }
+
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+        let span = ctx.span(id).expect("span is entered");
+        if let Some(frame) = span.extensions_mut().get_mut::<Frame>() {
+            frame.entered = Some(Instant::now());
+            frame.children = Duration::ZERO;
+        }
+    }
+
+    fn on_exit(&self, id: &span::Id, ctx: Context<'_, S>) {
+        let span = ctx.span(id).expect("span is exited");
+        let elapsed = {
+            let mut extensions = span.extensions_mut();
+            let Some(frame) = extensions.get_mut::<Frame>() else {
+                return;
+            };
+            let Some(elapsed) = frame.entered.take().map(|entered| entered.elapsed()) else {
+                return;
+            };
+            let mut stacks = self.0.0.lock().expect("no panics while holding the lock");
+            *stacks.entry(frame.stack.clone()).or_default() +=
+                elapsed.saturating_sub(frame.children);
+            elapsed
+        };
+        if let Some(parent) = span.parent()
+            && let Some(frame) = parent.extensions_mut().get_mut::<Frame>()
+        {
+            frame.children += elapsed;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::thread;
+
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    #[test]
+    fn test_folded() {
+        let folded = Folded::default();
+        let subscriber = tracing_subscriber::registry().with(folded.layer());
+        tracing::subscriber::with_default(subscriber, || {
+            let outer = tracing::info_span!("outer");
+            let _outer = outer.enter();
+            for _ in 0..2 {
+                let _inner = tracing::info_span!("inner").entered();
+                thread::sleep(Duration::from_millis(5));
+            }
+        });
+
+        let mut out = vec![];
+        folded.write(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<(&str, u128)> = out
+            .lines()
+            .map(|line| {
+                let (stack, micros) = line.rsplit_once(' ').unwrap();
+                (stack, micros.parse().unwrap())
+            })
+            .collect();
+        let [(outer, outer_micros), (inner, inner_micros)] = lines[..] else {
+            panic!("expected two stacks, got {out:?}");
+        };
+        assert_eq!(outer, "aoc2025::flame::test::outer");
+        assert_eq!(
+            inner,
+            "aoc2025::flame::test::outer;aoc2025::flame::test::inner"
+        );
+        assert!(inner_micros >= 10_000);
+        assert!(outer_micros < inner_micros);
+    }
+}
@@ -0,0 +1,98 @@
+use crate::solution;
+
+/// SplitMix64, small and good enough for puzzle inputs, and the same sequence for a
+/// seed on every platform and release.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `low..=high`.
+    pub fn between(&mut self, low: u64, high: u64) -> u64 {
+        assert!(low <= high, "empty range {low}..={high}");
+        match (high - low).checked_add(1) {
+            // multiply-shift rather than `%`, which favours the low values
+            Some(span) => low + ((u128::from(self.next_u64()) * u128::from(span)) >> 64) as u64,
+            None => self.next_u64(),
+        }
+    }
+
+    /// Uniform in `0..n`.
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "empty range 0..0");
+        self.between(0, n as u64 - 1) as usize
+    }
+
+    /// True `num` times out of `den`.
+    pub fn chance(&mut self, num: u64, den: u64) -> bool {
+        self.between(1, den) <= num
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// A random input for `day` with about `size` records, see [`solution::Solution::generate`].
+///
+/// Returns `None` when the day is not implemented or has no generator.
+pub fn generate(day: u8, size: usize, seed: u64) -> Option<String> {
+    solution::find(day)?.generate(size, seed)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rng() {
+        let mut rng = Rng::new(7);
+        let values: Vec<_> = (0..1000).map(|_| rng.between(3, 5)).collect();
+        assert!(values.iter().all(|v| (3..=5).contains(v)));
+        assert!((3..=5).all(|v| values.contains(&v)));
+        assert_eq!(Rng::new(7).next_u64(), Rng::new(7).next_u64());
+        assert_ne!(Rng::new(7).next_u64(), Rng::new(8).next_u64());
+        assert_eq!(Rng::new(1).between(0, u64::MAX), Rng::new(1).next_u64());
+
+        let mut items: Vec<_> = (0..20).collect();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..20).collect::<Vec<_>>());
+        items.sort_unstable();
+        assert_eq!(items, (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_generate() {
+        for entry in solution::DAYS {
+            let day = entry.day();
+            for seed in 0..3 {
+                let input =
+                    generate(day, 12, seed).unwrap_or_else(|| panic!("day {day} has no generator"));
+                assert_eq!(generate(day, 12, seed), Some(input.clone()));
+                for part in 1..=2 {
+                    if let Err(err) = entry.solve(part, &input) {
+                        panic!("day {day} part {part} seed {seed}: {err}\n{input}");
+                    }
+                }
+            }
+            assert_ne!(generate(day, 12, 0), generate(day, 12, 1));
+        }
+    }
+}
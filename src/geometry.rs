@@ -0,0 +1,229 @@
+use std::{
+    ops::{Add, Sub},
+    str::FromStr,
+};
+
+use glam::{I64Vec3, U64Vec2, USizeVec2};
+
+use crate::error::{Error, Result, parse_at};
+
+/// Parses a comma separated point like `x,y` or `x,y,z` from `line`, a slice of `input`.
+pub fn parse_point<T: FromStr, const N: usize>(input: &str, line: &str) -> Result<[T; N]> {
+    let coords: Vec<&str> = line.split(',').collect();
+    let Ok(coords) = <[&str; N]>::try_from(coords) else {
+        let expected = ["x", "y", "z", "w"][..N.min(4)].join(",");
+        return Err(Error::at(input, line, format!("expected `{expected}`")));
+    };
+    let point = coords
+        .iter()
+        .map(|coord| parse_at(input, coord.trim(), "a coordinate"))
+        .collect::<Result<Vec<T>>>()?;
+    Ok(point
+        .try_into()
+        .unwrap_or_else(|_| unreachable!("length checked above")))
+}
+
+/// Ways to measure the distance between two points in space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    /// Euclidean distance squared, which keeps the ordering without roots.
+    SquaredEuclidean,
+    Manhattan,
+    Chebyshev,
+}
+
+impl Metric {
+    pub fn distance(self, a: I64Vec3, b: I64Vec3) -> u64 {
+        match self {
+            Metric::SquaredEuclidean => a.distance_squared(b).unsigned_abs(),
+            Metric::Manhattan => a.manhattan_distance(b),
+            Metric::Chebyshev => a.chebyshev_distance(b),
+        }
+    }
+}
+
+/// Unsigned 2D vectors a [`Rect`] can be built from.
+pub trait Corner: Copy + Add<Output = Self> + Sub<Output = Self> {
+    type Scalar;
+    const ONE: Self;
+
+    fn lower(self, other: Self) -> Self;
+    fn upper(self, other: Self) -> Self;
+    /// Whether every component is greater or equal.
+    fn all_ge(self, other: Self) -> bool;
+    /// Whether every component is strictly greater.
+    fn all_gt(self, other: Self) -> bool;
+    fn product(self) -> Self::Scalar;
+    /// Points between `min` and `max` inclusive in row-major order.
+    fn span(min: Self, max: Self) -> impl Iterator<Item = Self> + 'static;
+}
+
+macro_rules! impl_corner {
+    ($vec:ty, $scalar:ty) => {
+        impl Corner for $vec {
+            type Scalar = $scalar;
+            const ONE: Self = <$vec>::ONE;
+
+            fn lower(self, other: Self) -> Self {
+                self.min(other)
+            }
+
+            fn upper(self, other: Self) -> Self {
+                self.max(other)
+            }
+
+            fn all_ge(self, other: Self) -> bool {
+                self.cmpge(other).all()
+            }
+
+            fn all_gt(self, other: Self) -> bool {
+                self.cmpgt(other).all()
+            }
+
+            fn product(self) -> $scalar {
+                self.element_product()
+            }
+
+            fn span(min: Self, max: Self) -> impl Iterator<Item = Self> + 'static {
+                (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| <$vec>::new(x, y)))
+            }
+        }
+    };
+}
+
+impl_corner!(U64Vec2, u64);
+impl_corner!(USizeVec2, usize);
+
+/// Axis-aligned rectangle including both corners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect<V> {
+    pub min: V,
+    pub max: V,
+}
+
+impl<V: Corner> Rect<V> {
+    /// Rectangle spanned by two opposite corners.
+    pub fn from_corners(a: V, b: V) -> Self {
+        Self {
+            min: a.lower(b),
+            max: a.upper(b),
+        }
+    }
+
+    /// Smallest rectangle containing all `points`, `None` when there are none.
+    pub fn bounding(points: impl IntoIterator<Item = V>) -> Option<Self> {
+        points
+            .into_iter()
+            .map(|point| Self::from_corners(point, point))
+            .reduce(|a, b| a.union(&b))
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: self.min.lower(other.min),
+            max: self.max.upper(other.max),
+        }
+    }
+
+    /// Number of points along each axis.
+    pub fn size(&self) -> V {
+        self.max - self.min + V::ONE
+    }
+
+    /// Number of points inside, edges included.
+    pub fn area(&self) -> V::Scalar {
+        self.size().product()
+    }
+
+    pub fn contains(&self, point: V) -> bool {
+        point.all_ge(self.min) && self.max.all_ge(point)
+    }
+
+    /// Whether the insides overlap, touching edges don't count.
+    pub fn interior_intersects(&self, other: &Self) -> bool {
+        self.max.all_gt(other.min) && other.max.all_gt(self.min)
+    }
+
+    /// Whether the axis-aligned segment from `a` to `b` cuts through the inside.
+    pub fn crossed_by(&self, a: V, b: V) -> bool {
+        self.interior_intersects(&Self::from_corners(a, b))
+    }
+
+    /// All points inside in row-major order.
+    pub fn points(&self) -> impl Iterator<Item = V> + use<V> {
+        V::span(self.min, self.max)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use glam::{i64vec3, u64vec2, usizevec2};
+    use rstest::*;
+
+    #[test]
+    fn test_parse_point() {
+        let input = "7,1\n1,2,3\n4,x";
+        let [x, y]: [u64; 2] = parse_point(input, &input[..3]).unwrap();
+        assert_eq!(u64vec2(x, y), u64vec2(7, 1));
+        let point: [i64; 3] = parse_point(input, &input[4..9]).unwrap();
+        assert_eq!(I64Vec3::from_array(point), i64vec3(1, 2, 3));
+
+        let err = parse_point::<u64, 2>(input, &input[4..9]).unwrap_err();
+        assert_eq!(err.to_string(), "2:1: expected `x,y`");
+        let err = parse_point::<u64, 2>(input, &input[10..]).unwrap_err();
+        assert_eq!(err.to_string(), "3:3: expected a coordinate, found `x`");
+    }
+
+    #[rstest]
+    #[case(Metric::SquaredEuclidean, 50)]
+    #[case(Metric::Manhattan, 12)]
+    #[case(Metric::Chebyshev, 5)]
+    fn test_metric(#[case] metric: Metric, #[case] expected: u64) {
+        assert_eq!(
+            metric.distance(i64vec3(1, 2, 3), i64vec3(-2, 6, 8)),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_area_and_bounds() {
+        let rect = Rect::from_corners(u64vec2(11, 1), u64vec2(2, 5));
+        assert_eq!(rect.min, u64vec2(2, 1));
+        assert_eq!(rect.area(), 50);
+        assert!(rect.contains(u64vec2(11, 5)));
+        assert!(!rect.contains(u64vec2(12, 5)));
+
+        let points = [u64vec2(7, 1), u64vec2(11, 7), u64vec2(2, 3)];
+        assert_eq!(
+            Rect::bounding(points),
+            Some(Rect::from_corners(u64vec2(2, 1), u64vec2(11, 7)))
+        );
+        assert_eq!(Rect::<U64Vec2>::bounding([]), None);
+    }
+
+    #[rstest]
+    #[case(u64vec2(5, 0), u64vec2(5, 10), true)]
+    #[case(u64vec2(2, 0), u64vec2(2, 10), false)]
+    #[case(u64vec2(0, 4), u64vec2(3, 4), true)]
+    #[case(u64vec2(0, 4), u64vec2(2, 4), false)]
+    #[case(u64vec2(0, 6), u64vec2(10, 6), false)]
+    fn test_crossed_by(#[case] a: U64Vec2, #[case] b: U64Vec2, #[case] expected: bool) {
+        let rect = Rect::from_corners(u64vec2(2, 1), u64vec2(8, 6));
+        assert_eq!(rect.crossed_by(a, b), expected);
+    }
+
+    #[test]
+    fn test_points() {
+        let rect = Rect::from_corners(usizevec2(1, 0), usizevec2(2, 1));
+        assert_eq!(
+            rect.points().collect::<Vec<_>>(),
+            vec![
+                usizevec2(1, 0),
+                usizevec2(2, 0),
+                usizevec2(1, 1),
+                usizevec2(2, 1)
+            ]
+        );
+    }
+}
@@ -0,0 +1,278 @@
+use std::{borrow::Borrow, collections::HashMap, hash::Hash, ops::Index};
+
+use crate::error::{Error, Result};
+
+/// Hands out dense ids `0..len` to keys in the order they are first seen.
+#[derive(Debug, Clone)]
+pub struct Interner<K> {
+    keys: Vec<K>,
+    ids: HashMap<K, usize>,
+}
+
+impl<K: Hash + Eq + Clone> Interner<K> {
+    pub fn new() -> Self {
+        Self {
+            keys: vec![],
+            ids: HashMap::new(),
+        }
+    }
+
+    /// Id of `key`, assigning the next free one if it is new.
+    pub fn intern(&mut self, key: K) -> usize {
+        *self.ids.entry(key).or_insert_with_key(|key| {
+            self.keys.push(key.clone());
+            self.keys.len() - 1
+        })
+    }
+
+    /// Id of `key`, which may be a borrowed form of the keys like `&str` for `String`.
+    pub fn get<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.ids.get(key).copied()
+    }
+
+    pub fn key(&self, id: usize) -> &K {
+        &self.keys[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+impl<K: Hash + Eq + Clone> Default for Interner<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq + Clone> FromIterator<K> for Interner<K> {
+    fn from_iter<I: IntoIterator<Item = K>>(keys: I) -> Self {
+        let mut interner = Self::new();
+        for key in keys {
+            interner.intern(key);
+        }
+        interner
+    }
+}
+
+impl<K: Hash + Eq + Clone> Index<K> for Interner<K> {
+    type Output = usize;
+
+    fn index(&self, key: K) -> &usize {
+        &self.ids[&key]
+    }
+}
+
+/// Adjacency lists of nodes `0..len` packed into one buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Graph {
+    /// Neighbours of `node` are `targets[offsets[node]..offsets[node + 1]]`.
+    offsets: Vec<usize>,
+    targets: Vec<usize>,
+}
+
+impl Graph {
+    /// Graph with an edge from `a` to `b` for every `(a, b)` in `edges`.
+    pub fn directed(len: usize, edges: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        let mut edges: Vec<_> = edges.into_iter().collect();
+        edges.sort_unstable();
+
+        let mut offsets = vec![0; len + 1];
+        for &(from, _) in &edges {
+            offsets[from + 1] += 1;
+        }
+        for node in 0..len {
+            offsets[node + 1] += offsets[node];
+        }
+        Self {
+            offsets,
+            targets: edges.into_iter().map(|(_, to)| to).collect(),
+        }
+    }
+
+    /// Graph with edges both ways for every `(a, b)` in `edges`.
+    pub fn undirected(len: usize, edges: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        Self::directed(len, edges.into_iter().flat_map(|(a, b)| [(a, b), (b, a)]))
+    }
+
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn neighbours(&self, node: usize) -> &[usize] {
+        &self.targets[self.offsets[node]..self.offsets[node + 1]]
+    }
+
+    /// Nodes ordered so every edge points forward, fails on cycles.
+    pub fn topo_order(&self) -> Result<Vec<usize>> {
+        let mut incoming = vec![0; self.len()];
+        for &to in &self.targets {
+            incoming[to] += 1;
+        }
+        let mut order: Vec<_> = (0..self.len())
+            .filter(|&node| incoming[node] == 0)
+            .collect();
+        let mut next = 0;
+        while let Some(&node) = order.get(next) {
+            next += 1;
+            for &to in self.neighbours(node) {
+                incoming[to] -= 1;
+                if incoming[to] == 0 {
+                    order.push(to);
+                }
+            }
+        }
+        if order.len() == self.len() {
+            Ok(order)
+        } else {
+            Err(Error::puzzle("graph has a cycle"))
+        }
+    }
+
+    /// Groups of nodes linked by edges in either direction, ordered by smallest node.
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let mut sets = UnionFind::new(self.len());
+        for node in 0..self.len() {
+            for &to in self.neighbours(node) {
+                sets.union(node, to);
+            }
+        }
+        let mut ids = Interner::new();
+        let mut components: Vec<Vec<usize>> = vec![];
+        for node in 0..self.len() {
+            let id = ids.intern(sets.find(node));
+            if id == components.len() {
+                components.push(vec![]);
+            }
+            components[id].push(node);
+        }
+        components
+    }
+}
+
+/// Disjoint sets of `0..len` with union by size and path compression.
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    sets: usize,
+}
+
+impl UnionFind {
+    pub fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+            sets: len,
+        }
+    }
+
+    /// Representative of the set holding `node`.
+    pub fn find(&mut self, node: usize) -> usize {
+        let mut root = node;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut node = node;
+        while self.parent[node] != root {
+            node = std::mem::replace(&mut self.parent[node], root);
+        }
+        root
+    }
+
+    /// Merges the sets of `a` and `b`, `false` if they were already one.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (big, small) = if self.size[a] >= self.size[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parent[small] = big;
+        self.size[big] += self.size[small];
+        self.sets -= 1;
+        true
+    }
+
+    /// Number of nodes in the set holding `node`.
+    pub fn set_size(&mut self, node: usize) -> usize {
+        let root = self.find(node);
+        self.size[root]
+    }
+
+    /// Number of disjoint sets left.
+    pub fn sets(&self) -> usize {
+        self.sets
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_interner() {
+        let mut names = Interner::new();
+        assert_eq!(names.intern("you"), 0);
+        assert_eq!(names.intern("out"), 1);
+        assert_eq!(names.intern("you"), 0);
+        assert_eq!(names.get("svr"), None);
+        assert_eq!(names["out"], 1);
+        assert_eq!(*names.key(1), "out");
+        assert_eq!(names.len(), 2);
+
+        let mut owned: Interner<String> = ["you", "out"].map(String::from).into_iter().collect();
+        assert_eq!(owned.get("out"), Some(1));
+        assert_eq!(owned.intern("svr".to_string()), 2);
+    }
+
+    #[test]
+    fn test_directed() {
+        let graph = Graph::directed(4, [(2, 3), (0, 2), (0, 1), (1, 3)]);
+        assert_eq!(graph.len(), 4);
+        assert_eq!(graph.neighbours(0), &[1, 2]);
+        assert_eq!(graph.neighbours(3), &[] as &[usize]);
+        assert_eq!(graph.topo_order().unwrap(), vec![0, 1, 2, 3]);
+
+        let cyclic = Graph::directed(3, [(0, 1), (1, 2), (2, 1)]);
+        assert_eq!(
+            cyclic.topo_order().unwrap_err().to_string(),
+            "graph has a cycle"
+        );
+    }
+
+    #[test]
+    fn test_components() {
+        let graph = Graph::undirected(6, [(4, 0), (1, 5), (0, 2)]);
+        assert_eq!(graph.neighbours(0), &[2, 4]);
+        assert_eq!(graph.components(), vec![vec![0, 2, 4], vec![1, 5], vec![3]]);
+    }
+
+    #[test]
+    fn test_union_find() {
+        let mut sets = UnionFind::new(5);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(3, 4));
+        assert!(sets.union(1, 4));
+        assert!(!sets.union(0, 3));
+        assert_eq!(sets.find(0), sets.find(4));
+        assert_eq!(sets.set_size(3), 4);
+        assert_eq!(sets.set_size(2), 1);
+        assert_eq!(sets.sets(), 2);
+    }
+}
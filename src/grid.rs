@@ -0,0 +1,262 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use glam::{IVec2, USizeVec2, ivec2, usizevec2};
+
+use crate::error::{Error, Result};
+
+/// Offsets of the 4 orthogonal neighbours.
+pub const NEIGHBOURS4: [IVec2; 4] = [ivec2(0, -1), ivec2(1, 0), ivec2(0, 1), ivec2(-1, 0)];
+
+/// Offsets of all 8 neighbours, including diagonals.
+pub const NEIGHBOURS8: [IVec2; 8] = [
+    ivec2(-1, -1),
+    ivec2(0, -1),
+    ivec2(1, -1),
+    ivec2(-1, 0),
+    ivec2(1, 0),
+    ivec2(-1, 1),
+    ivec2(0, 1),
+    ivec2(1, 1),
+];
+
+/// Rectangular map stored row by row in one buffer.
+///
+/// Positions are `x` for the column and `y` for the row, starting top left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Builds a grid from equally long rows, `None` if they are ragged.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let width = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        Some(Self {
+            width,
+            height: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Parses one row per line, mapping each char with `cell`.
+    ///
+    /// Fails on chars rejected by `cell` and on lines of different length.
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> Option<T>) -> Result<Self> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = vec![];
+        for line in input.lines() {
+            for (pos, c) in line.char_indices() {
+                cells.push(
+                    cell(c).ok_or_else(|| {
+                        Error::at(input, &line[pos..], format!("unexpected `{c}`"))
+                    })?,
+                );
+            }
+            let len = line.chars().count();
+            if *width.get_or_insert(len) != len {
+                return Err(Error::at(
+                    input,
+                    line,
+                    format!("expected {} columns", width.unwrap_or_default()),
+                ));
+            }
+            height += 1;
+        }
+        match width {
+            Some(width) if width > 0 => Ok(Self {
+                width,
+                height,
+                cells,
+            }),
+            _ => Err(Error::at_offset(input, 0, "expected a grid")),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn size(&self) -> USizeVec2 {
+        usizevec2(self.width, self.height)
+    }
+
+    pub fn contains(&self, pos: USizeVec2) -> bool {
+        pos.x < self.width && pos.y < self.height
+    }
+
+    pub fn get(&self, pos: USizeVec2) -> Option<&T> {
+        self.contains(pos)
+            .then(|| &self.cells[pos.y * self.width + pos.x])
+    }
+
+    pub fn get_mut(&mut self, pos: USizeVec2) -> Option<&mut T> {
+        self.contains(pos)
+            .then(|| &mut self.cells[pos.y * self.width + pos.x])
+    }
+
+    /// Moves `pos` by `delta`, `None` when it leaves the grid.
+    pub fn offset(&self, pos: USizeVec2, delta: IVec2) -> Option<USizeVec2> {
+        let moved = usizevec2(
+            pos.x.checked_add_signed(delta.x as isize)?,
+            pos.y.checked_add_signed(delta.y as isize)?,
+        );
+        self.contains(moved).then_some(moved)
+    }
+
+    /// All positions in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = USizeVec2> + use<T> {
+        let (width, height) = (self.width, self.height);
+        (0..height).flat_map(move |y| (0..width).map(move |x| usizevec2(x, y)))
+    }
+
+    /// Orthogonal neighbours of `pos` inside the grid.
+    pub fn neighbours4(&self, pos: USizeVec2) -> impl Iterator<Item = USizeVec2> + '_ {
+        NEIGHBOURS4
+            .iter()
+            .filter_map(move |&delta| self.offset(pos, delta))
+    }
+
+    /// Orthogonal and diagonal neighbours of `pos` inside the grid.
+    pub fn neighbours8(&self, pos: USizeVec2) -> impl Iterator<Item = USizeVec2> + '_ {
+        NEIGHBOURS8
+            .iter()
+            .filter_map(move |&delta| self.offset(pos, delta))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1)).take(self.height)
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {x} out of bounds");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+}
+
+impl<T> Index<USizeVec2> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: USizeVec2) -> &Self::Output {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos} is outside of {}", self.size()))
+    }
+}
+
+impl<T> IndexMut<USizeVec2> for Grid<T> {
+    fn index_mut(&mut self, pos: USizeVec2) -> &mut Self::Output {
+        let size = self.size();
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos} is outside of {size}"))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const INPUT: &str = "#..\n.#.\n..#\n#.#";
+
+    fn parse_bool(c: char) -> Option<bool> {
+        match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let grid = Grid::parse(INPUT, Some).unwrap();
+        assert_eq!(grid.size(), usizevec2(3, 4));
+        assert_eq!(grid.to_string(), INPUT);
+        assert_eq!(grid[usizevec2(2, 3)], '#');
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Grid::parse("#..\n.x.", parse_bool).unwrap_err();
+        assert_eq!(err.to_string(), "2:2: unexpected `x`");
+        let err = Grid::parse("#..\n..", parse_bool).unwrap_err();
+        assert_eq!(err.to_string(), "2:1: expected 3 columns");
+        assert!(Grid::parse("", parse_bool).is_err());
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = Grid::parse(INPUT, parse_bool).unwrap();
+        assert_eq!(grid.row(1), &[false, true, false]);
+        assert_eq!(grid.rows().count(), 4);
+        assert_eq!(
+            grid.column(2).copied().collect::<Vec<_>>(),
+            vec![false, false, true, true]
+        );
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::parse(INPUT, parse_bool).unwrap();
+        assert_eq!(grid.neighbours4(usizevec2(0, 0)).count(), 2);
+        assert_eq!(grid.neighbours8(usizevec2(0, 0)).count(), 3);
+        assert_eq!(grid.neighbours4(usizevec2(1, 1)).count(), 4);
+        assert_eq!(grid.neighbours8(usizevec2(1, 1)).count(), 8);
+        assert_eq!(grid.neighbours8(usizevec2(2, 3)).count(), 3);
+    }
+
+    #[test]
+    fn test_offset() {
+        let grid = Grid::new(3, 2, 0u8);
+        assert_eq!(
+            grid.offset(usizevec2(1, 1), ivec2(1, -1)),
+            Some(usizevec2(2, 0))
+        );
+        assert_eq!(grid.offset(usizevec2(0, 1), ivec2(-1, 0)), None);
+        assert_eq!(grid.offset(usizevec2(2, 1), ivec2(0, 1)), None);
+        assert_eq!(grid.get(usizevec2(3, 0)), None);
+    }
+}
@@ -0,0 +1,151 @@
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::error::Result;
+
+/// Directory holding the inputs, relative to the crate root.
+pub const INPUT_DIR: &str = "input";
+
+/// Profile made of the inputs directly in the input directory.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Root of this checkout, holding `src/`, the input directory and `aoc2025.conf`,
+/// wherever the process runs from.
+pub fn crate_root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Input directory of this checkout, wherever the process runs from.
+pub fn root() -> PathBuf {
+    crate_root().join(INPUT_DIR)
+}
+
+/// Reads `path`, `None` when there is no such file.
+///
+/// Inputs can't be redistributed, so a fresh clone may not have them.
+pub fn read(path: &Path) -> Result<Option<String>> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => Ok(Some(text)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Appends `line` to the file at `path`, creating it and its directory if needed.
+pub fn append_line(path: &Path, line: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{line}")?;
+    Ok(())
+}
+
+/// One set of inputs with its own manifest, usually from one account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    pub name: String,
+    pub dir: PathBuf,
+}
+
+impl Profile {
+    /// Profile `name` under `root`, which is `root` itself for the default one.
+    pub fn new(root: &Path, name: &str) -> Self {
+        let dir = if name == DEFAULT_PROFILE {
+            root.to_path_buf()
+        } else {
+            root.join(name)
+        };
+        Self {
+            name: name.to_string(),
+            dir,
+        }
+    }
+
+    /// Path of the input of `day`.
+    pub fn day_path(&self, day: u8) -> PathBuf {
+        self.dir.join(format!("day{day:02}.txt"))
+    }
+
+    /// Input of `day`, `None` when it is missing or still the empty file left by `new`.
+    pub fn load(&self, day: u8) -> Result<Option<String>> {
+        Ok(read(&self.day_path(day))?.filter(|input| !input.is_empty()))
+    }
+}
+
+/// The default profile in `root` followed by one per subdirectory, by name.
+pub fn profiles(root: &Path) -> Result<Vec<Profile>> {
+    let mut names = vec![];
+    let entries = match std::fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            return Ok(vec![Profile::new(root, DEFAULT_PROFILE)]);
+        }
+        Err(err) => return Err(err.into()),
+    };
+    for entry in entries {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            names.push(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+    names.sort();
+    Ok(std::iter::once(DEFAULT_PROFILE)
+        .chain(names.iter().map(String::as_str))
+        .map(|name| Profile::new(root, name))
+        .collect())
+}
+
+/// Fresh directory in the system temp dir for a test, removed with everything in it
+/// when dropped, even by a failed assertion.
+#[cfg(test)]
+pub(crate) struct Scratch(PathBuf);
+
+#[cfg(test)]
+impl Scratch {
+    pub(crate) fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("aoc2025-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+}
+
+#[cfg(test)]
+impl std::ops::Deref for Scratch {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_load() {
+        let example = Profile::new(&root(), "example");
+        assert_eq!(example.day_path(4), root().join("example/day04.txt"));
+        assert!(example.load(4).unwrap().unwrap().starts_with("..@@.@@@@."));
+        assert_eq!(example.load(25).unwrap(), None);
+        assert_eq!(profiles(&root().join("missing")).unwrap().len(), 1);
+
+        let dir = Scratch::new("load");
+        std::fs::write(dir.join("day01.txt"), "").unwrap();
+        assert_eq!(Profile::new(&dir, DEFAULT_PROFILE).load(1).unwrap(), None);
+    }
+}
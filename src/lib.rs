@@ -1,13 +1,48 @@
-#![allow(dead_code)]
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
+pub mod alloc;
+pub mod answer;
+pub mod bench;
+pub mod differential;
+pub mod error;
+pub mod fetch;
+pub mod flame;
+pub mod generator;
+pub mod geometry;
+pub mod graph;
+pub mod grid;
+pub mod input;
+pub mod logging;
+pub mod minimize;
+pub mod parallel;
+pub mod report;
+pub mod scaffold;
+pub mod solution;
+pub mod stream;
+pub mod submit;
+pub mod verify;
+
+// `mod dayNN;` for every `src/dayNN.rs`, see build.rs.
+include!(concat!(env!("OUT_DIR"), "/days.rs"));
+
+/// Runs `part` of `day` against `input`.
+///
+/// Returns `None` when the day or part is not implemented.
+pub fn solve(day: u8, part: u8, input: &str) -> error::Result<Option<answer::Answer>> {
+    match solution::find(day) {
+        Some(entry) => entry.solve(part, input),
+        None => Ok(None),
+    }
+}
+
+/// Runs `part` of `day` against input read from `reader`, see [`solution::Solution::stream`].
+///
+/// Returns `None` when the day or part is not implemented.
+pub fn solve_stream(
+    day: u8,
+    part: u8,
+    reader: &mut dyn std::io::BufRead,
+) -> error::Result<Option<answer::Answer>> {
+    match solution::find(day) {
+        Some(entry) => entry.stream(part, reader),
+        None => Ok(None),
+    }
+}
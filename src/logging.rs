@@ -0,0 +1,145 @@
+use std::{
+    fmt,
+    fs::File,
+    io::{self, IsTerminal},
+    path::PathBuf,
+    str::FromStr,
+    sync::Mutex,
+};
+
+use tracing::Subscriber;
+use tracing_subscriber::{EnvFilter, Layer, fmt::writer::BoxMakeWriter, registry::LookupSpan};
+
+use crate::error::{Error, Result};
+
+/// How events are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Multi-line, with the fields of each event on their own lines.
+    Pretty,
+    /// One line per event, with the names of the spans it is in.
+    #[default]
+    Compact,
+    /// One JSON object per line, with the fields of the spans it is in.
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pretty" => Ok(Format::Pretty),
+            "compact" => Ok(Format::Compact),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown log format `{s}`")),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Format::Pretty => write!(f, "pretty"),
+            Format::Compact => write!(f, "compact"),
+            Format::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// What is logged, how and where to.
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    /// `EnvFilter` directive such as `aoc2025::day10=info`, `RUST_LOG` or `info` when unset.
+    pub directive: Option<String>,
+    pub format: Format,
+    /// Written to instead of stderr, truncated first.
+    pub file: Option<PathBuf>,
+}
+
+impl Options {
+    /// Layer writing the events the directive lets through.
+    ///
+    /// The filter only applies to this layer, so others still see every span.
+    pub fn layer<S>(&self) -> Result<Box<dyn Layer<S> + Send + Sync>>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        let directive = match &self.directive {
+            Some(directive) => directive.clone(),
+            None => std::env::var(EnvFilter::DEFAULT_ENV).unwrap_or_else(|_| "info".to_string()),
+        };
+        let filter = EnvFilter::try_new(&directive).map_err(|err| {
+            Error::at_offset(&directive, 0, format!("invalid log directive: {err}"))
+        })?;
+        let (writer, ansi) = match &self.file {
+            Some(path) => (BoxMakeWriter::new(Mutex::new(File::create(path)?)), false),
+            None => (BoxMakeWriter::new(io::stderr), io::stderr().is_terminal()),
+        };
+
+        let layer = tracing_subscriber::fmt::layer()
+            .with_writer(writer)
+            .with_ansi(ansi);
+        Ok(match self.format {
+            Format::Pretty => layer.pretty().with_filter(filter).boxed(),
+            Format::Compact => layer.compact().with_filter(filter).boxed(),
+            Format::Json => layer.json().with_filter(filter).boxed(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+    use crate::input::Scratch;
+
+    #[test]
+    fn test_format() {
+        for format in [Format::Pretty, Format::Compact, Format::Json] {
+            assert_eq!(format.to_string().parse(), Ok(format));
+        }
+        assert_eq!(
+            "yaml".parse::<Format>(),
+            Err("unknown log format `yaml`".to_string())
+        );
+    }
+
+    #[test]
+    fn test_json_file() {
+        let dir = Scratch::new("log");
+        let path = dir.join("log.json");
+        let options = Options {
+            directive: Some("aoc2025::logging=info,aoc2025::logging::test::quiet=off".to_string()),
+            format: Format::Json,
+            file: Some(path.clone()),
+        };
+        let subscriber = tracing_subscriber::registry().with(options.layer().unwrap());
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(answer = 42, "logged");
+            tracing::debug!("too verbose");
+            tracing::info!(target: "aoc2025::logging::test::quiet", "filtered out");
+        });
+        let text = std::fs::read_to_string(&path).unwrap();
+
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines.len(), 1, "{text}");
+        assert!(lines[0].starts_with('{') && lines[0].ends_with('}'));
+        assert!(lines[0].contains(r#""answer":42"#));
+        assert!(lines[0].contains(r#""message":"logged""#));
+    }
+
+    #[test]
+    fn test_invalid_directive() {
+        let options = Options {
+            directive: Some("aoc2025=loud".to_string()),
+            ..Options::default()
+        };
+        let err = options
+            .layer::<tracing_subscriber::Registry>()
+            .err()
+            .unwrap();
+        assert!(err.to_string().starts_with("1:1: invalid log directive"));
+    }
+}
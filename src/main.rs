@@ -0,0 +1,591 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter},
+    path::Path,
+    process::ExitCode,
+    time::{Duration, Instant},
+};
+
+use aoc2025::{
+    alloc, bench, differential,
+    error::Error,
+    fetch::{self, CONFIG_FILE, Fetcher},
+    flame::Folded,
+    generator,
+    input::{self, DEFAULT_PROFILE, Profile},
+    logging,
+    minimize::{self, Shape},
+    report, scaffold, solution,
+    submit::{Submitter, Verdict},
+    verify::{self, Status},
+};
+use tracing_subscriber::{layer::SubscriberExt, registry};
+
+const USAGE: &str = "Usage: aoc2025 run <day> <part> [--profile <name>|<file>.folded]
+                   [--folded <path>] [--log <directive>]
+                   [--log-format pretty|compact|json]
+                   [--log-file <path>] [--jobs <n>] [<input>|-]
+       aoc2025 run --all [--profile <name>] [--report json|md] [--jobs <n>]
+       aoc2025 fetch <day> [--profile <name>]
+       aoc2025 submit <day> <part> [--profile <name>]
+       aoc2025 bench <day> [<part>] [--profile <name>] [--runs <n>]
+                     [--compare <rev> [--threshold <percent>]]
+       aoc2025 verify
+       aoc2025 new <day>
+       aoc2025 gen <day> [--size <n>] [--seed <n>]
+       aoc2025 diff [<check>] [--seeds <n>] [--size <n>]
+       aoc2025 minimize (<day> <part> | --check <name>) <input>
+
+run     Runs a solution against <input> (stdin when `-`, dayNN.txt of the profile when omitted).
+        Built with the count-alloc feature, it also reports the heap use of the solution.
+        --folded, or --profile with a <path> ending in .folded, writes the time
+        spent in each stack of tracing spans to <path>.
+        --log shows the tracing events an env-filter <directive> such as
+        aoc2025::day10=info lets through (RUST_LOG or info by default), on stderr
+        or in --log-file.
+        --jobs splits the records of days 2, 3, 10 and 12 between <n> threads
+        (0 for one per core, 1 by default).
+        --all runs every part once on dayNN.txt of the profile, and --report
+        writes the answers, expected answers, times and input hashes as JSON or Markdown.
+        With --all, --jobs runs <n> days at once instead, still listed in day order.
+fetch   Downloads dayNN.txt of the profile unless it is there already.
+submit  Solves dayNN.txt of the profile and posts the answer, unless the
+        profile's submissions.txt already rules it out.
+bench   Times parse and solve of the part (both parts when omitted) on dayNN.txt of
+        the profile, <n> runs (100 by default) after a warmup. Results are added to
+        bench-history.txt, --compare flags medians more than <percent> (10 by default)
+        slower than those saved at the git revision <rev>.
+verify  Checks every solution against the answers of every profile.
+new     Writes src/dayNN.rs from templates/day.rs and an empty input/dayNN.txt,
+        unless the day exists.
+gen     Prints a random input for the day with about <n> records (100 by default),
+        the same one for the same <seed> (0 by default).
+diff    Runs the checks whose name starts with <check> (all by default) on generated
+        inputs of seeds 0 to <n> (100 by default), comparing an optimized solver with a
+        reference, and prints the first input they disagree on, minimized into a test.
+minimize Drops lines, records or grid rows and columns of <input> as long as the part
+        still errors or panics on it, or the check <name> still disagrees, and prints
+        the smallest input left as a test for the day's test module.
+
+Profiles are input/ itself (`default`) and each of its subdirectories, holding
+dayNN.txt inputs and an answers.txt manifest. Missing inputs are fetched with the
+session of the profile from aoc2025.conf. input/, src/, aoc2025.conf and
+bench-history.txt are those of the checkout the binary was built from, wherever it runs.";
+
+/// Splits a leading `--profile <name>` off `args`.
+fn profile_arg(args: &[String]) -> (&str, &[String]) {
+    match args {
+        [flag, name, rest @ ..] if flag == "--profile" => (name.as_str(), rest),
+        _ => (DEFAULT_PROFILE, args),
+    }
+}
+
+fn fetcher() -> Result<Fetcher, String> {
+    let config = fetch::Config::load(&input::crate_root().join(CONFIG_FILE))
+        .map_err(|err| format!("{CONFIG_FILE}: {err}"))?;
+    Ok(Fetcher::new(config, &input::root()))
+}
+
+fn fetch_input(day: u8, profile: &str) -> Result<String, String> {
+    let profile = Profile::new(&input::root(), profile);
+    fetcher()?
+        .fetch(&profile, day)
+        .map_err(|err| format!("failed to fetch day {day}: {err}"))
+}
+
+fn read_input(day: u8, profile: &str, path: Option<&str>) -> Result<String, String> {
+    let read = |path: &Path| {
+        aoc2025::input::read(path)
+            .map_err(|err| format!("failed to read {}: {err}", path.display()))?
+            .ok_or_else(|| format!("input missing: {}", path.display()))
+    };
+    match path {
+        Some(path) => read(Path::new(path)),
+        None => {
+            let loaded = Profile::new(&input::root(), profile).load(day);
+            match loaded.map_err(|err| format!("failed to read day {day}: {err}"))? {
+                Some(input) => Ok(input),
+                None => fetch_input(day, profile),
+            }
+        }
+    }
+}
+
+fn jobs_arg(value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid job count `{value}`"))
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    if let [flag, rest @ ..] = args
+        && flag == "--all"
+    {
+        return run_all(rest);
+    }
+    let [day, part, rest @ ..] = args else {
+        return Err(USAGE.to_string());
+    };
+    let day: u8 = day.parse().map_err(|_| format!("invalid day `{day}`"))?;
+    let part: u8 = part.parse().map_err(|_| format!("invalid part `{part}`"))?;
+    let (mut profile, mut folded, mut jobs, mut rest) = (DEFAULT_PROFILE, None, 1, rest);
+    let (mut log, mut logging) = (false, logging::Options::default());
+    while let [flag, value, tail @ ..] = rest
+        && flag.starts_with("--")
+    {
+        match flag.as_str() {
+            // input profiles are directories, a `.folded` file is where spans go
+            "--profile" if value.ends_with(".folded") => folded = Some(Path::new(value)),
+            "--profile" => profile = value,
+            "--folded" => folded = Some(Path::new(value)),
+            "--log" => logging.directive = Some(value.clone()),
+            "--log-format" => logging.format = value.parse()?,
+            "--log-file" => logging.file = Some(value.into()),
+            "--jobs" => jobs = jobs_arg(value)?,
+            _ => return Err(USAGE.to_string()),
+        }
+        log |= flag.starts_with("--log");
+        rest = tail;
+    }
+    // stdin is handed to the solution as it arrives instead of being read up front
+    let input = match rest {
+        [path] if path == "-" => None,
+        [] => Some(read_input(day, profile, None)?),
+        [path] => Some(read_input(day, profile, Some(path))?),
+        _ => return Err(USAGE.to_string()),
+    };
+
+    let log = log
+        .then(|| logging.layer())
+        .transpose()
+        .map_err(|err| format!("log: {err}"))?;
+    let spans = Folded::default();
+    let solve = || {
+        let start = Instant::now();
+        let _span = tracing::info_span!("run", day, part).entered();
+        let (answer, allocs) = alloc::measure(|| match (solution::find(day), &input) {
+            (Some(entry), Some(input)) => entry.solve_with(part, input, jobs),
+            (Some(entry), None) => entry.stream_with(part, &mut io::stdin().lock(), jobs),
+            (None, _) => Ok(None),
+        });
+        (answer, allocs, start.elapsed())
+    };
+    let (answer, allocs, elapsed) = if log.is_some() || folded.is_some() {
+        let subscriber = registry().with(log).with(folded.map(|_| spans.layer()));
+        tracing::subscriber::with_default(subscriber, solve)
+    } else {
+        solve()
+    };
+    let answer = answer
+        .map_err(|err| format!("day {day} part {part}: {err}"))?
+        .ok_or_else(|| format!("day {day} part {part} is not implemented"))?;
+
+    println!("{answer}");
+    match allocs {
+        Some(allocs) => eprintln!("day {day:02} part {part}: {elapsed:.2?}, {allocs}"),
+        None => eprintln!("day {day:02} part {part}: {elapsed:.2?}"),
+    }
+    if let Some(path) = folded {
+        File::create(path)
+            .and_then(|file| spans.write(BufWriter::new(file)))
+            .map_err(|err| format!("failed to write {}: {err}", path.display()))?;
+    }
+    Ok(())
+}
+
+fn run_all(args: &[String]) -> Result<(), String> {
+    let (mut profile, mut format, mut jobs, mut rest) = (DEFAULT_PROFILE, None, 1, args);
+    while let [flag, value, tail @ ..] = rest {
+        match flag.as_str() {
+            "--profile" => profile = value,
+            "--report" => format = Some(value.parse()?),
+            "--jobs" => jobs = jobs_arg(value)?,
+            _ => return Err(USAGE.to_string()),
+        }
+        rest = tail;
+    }
+    if !rest.is_empty() {
+        return Err(USAGE.to_string());
+    }
+    let rows = report::run_all(&Profile::new(&input::root(), profile), jobs)
+        .map_err(|err| format!("run: {err}"))?;
+
+    match format {
+        Some(report::Format::Json) => print!("{}", report::json(profile, &rows)),
+        Some(report::Format::Markdown) => print!("{}", report::markdown(&rows)),
+        None => {
+            println!(
+                "day part {:<16} {:>10} {:>10} status",
+                "answer", "parse", "solve"
+            );
+            for row in &rows {
+                let answer = row
+                    .answer
+                    .as_ref()
+                    .map_or("-".to_string(), |a| a.to_string());
+                let time =
+                    |time: Option<Duration>| time.map_or("-".to_string(), |t| format!("{t:.2?}"));
+                println!(
+                    "{:>3} {:>4} {answer:<16} {:>10} {:>10} {}",
+                    row.day,
+                    row.part,
+                    time(row.parse),
+                    time(row.solve),
+                    row.status
+                );
+            }
+        }
+    }
+    let failed = rows
+        .iter()
+        .filter(|row| matches!(row.status, report::Status::Fail | report::Status::Error(_)))
+        .count();
+    if failed > 0 {
+        Err(format!("{failed} parts failed"))
+    } else {
+        Ok(())
+    }
+}
+
+fn verify(args: &[String]) -> Result<(), String> {
+    if !args.is_empty() {
+        return Err(USAGE.to_string());
+    }
+    let checks = verify::verify(&input::root()).map_err(|err| format!("verify: {err}"))?;
+
+    println!("{:<10} day part {:<12} status", "profile", "input");
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for check in &checks {
+        match check.status {
+            Status::Pass => passed += 1,
+            Status::Fail { .. } | Status::Error(_) => failed += 1,
+            Status::Missing(_) => missing += 1,
+        }
+        println!(
+            "{:<10} {:>3} {:>4} {:<12} {}",
+            check.profile, check.day, check.part, check.input, check.status
+        );
+    }
+    println!("{passed} passed, {failed} failed, {missing} missing");
+
+    if failed > 0 {
+        Err(format!("{failed} checks failed"))
+    } else {
+        Ok(())
+    }
+}
+
+fn fetch(args: &[String]) -> Result<(), String> {
+    let [day, rest @ ..] = args else {
+        return Err(USAGE.to_string());
+    };
+    let day: u8 = day.parse().map_err(|_| format!("invalid day `{day}`"))?;
+    let (profile, []) = profile_arg(rest) else {
+        return Err(USAGE.to_string());
+    };
+    let input = fetch_input(day, profile)?;
+    let path = Profile::new(&input::root(), profile).day_path(day);
+    println!("{}: {} lines", path.display(), input.lines().count());
+    Ok(())
+}
+
+fn submit(args: &[String]) -> Result<(), String> {
+    let [day, part, rest @ ..] = args else {
+        return Err(USAGE.to_string());
+    };
+    let day: u8 = day.parse().map_err(|_| format!("invalid day `{day}`"))?;
+    let part: u8 = part.parse().map_err(|_| format!("invalid part `{part}`"))?;
+    let (profile, []) = profile_arg(rest) else {
+        return Err(USAGE.to_string());
+    };
+    let input = read_input(day, profile, None)?;
+    let answer = aoc2025::solve(day, part, &input)
+        .map_err(|err| format!("day {day} part {part}: {err}"))?
+        .ok_or_else(|| format!("day {day} part {part} is not implemented"))?;
+    println!("{answer}");
+
+    let profile = Profile::new(&input::root(), profile);
+    let verdict = Submitter::new(fetcher()?)
+        .submit(&profile, day, part, &answer)
+        .map_err(|err| format!("day {day} part {part}: {err}"))?;
+    eprintln!("day {day:02} part {part}: {verdict}");
+    if matches!(verdict, Verdict::Right | Verdict::AlreadySolved) {
+        Ok(())
+    } else {
+        Err(format!("day {day} part {part} is not solved yet"))
+    }
+}
+
+fn new(args: &[String]) -> Result<(), String> {
+    let [day] = args else {
+        return Err(USAGE.to_string());
+    };
+    let day: u8 = day.parse().map_err(|_| format!("invalid day `{day}`"))?;
+    let path =
+        scaffold::scaffold(input::crate_root(), day).map_err(|err| format!("day {day}: {err}"))?;
+    println!("{}", path.display());
+    Ok(())
+}
+
+fn generate(args: &[String]) -> Result<(), String> {
+    let [day, rest @ ..] = args else {
+        return Err(USAGE.to_string());
+    };
+    let day: u8 = day.parse().map_err(|_| format!("invalid day `{day}`"))?;
+    let (mut size, mut seed, mut rest) = (100, 0, rest);
+    while let [flag, value, tail @ ..] = rest {
+        match flag.as_str() {
+            "--size" => {
+                size = value
+                    .parse()
+                    .map_err(|_| format!("invalid size `{value}`"))?
+            }
+            "--seed" => {
+                seed = value
+                    .parse()
+                    .map_err(|_| format!("invalid seed `{value}`"))?
+            }
+            _ => return Err(USAGE.to_string()),
+        }
+        rest = tail;
+    }
+    if !rest.is_empty() {
+        return Err(USAGE.to_string());
+    }
+    let input = generator::generate(day, size, seed)
+        .ok_or_else(|| format!("day {day} has no generator"))?;
+    print!("{input}");
+    Ok(())
+}
+
+fn diff(args: &[String]) -> Result<(), String> {
+    let (prefix, mut rest) = match args {
+        [name, rest @ ..] if !name.starts_with("--") => (name.as_str(), rest),
+        _ => ("", args),
+    };
+    let (mut seeds, mut size) = (100, None);
+    while let [flag, value, tail @ ..] = rest {
+        match flag.as_str() {
+            "--seeds" => {
+                seeds = value
+                    .parse()
+                    .map_err(|_| format!("invalid seed count `{value}`"))?
+            }
+            "--size" => {
+                size = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid size `{value}`"))?,
+                )
+            }
+            _ => return Err(USAGE.to_string()),
+        }
+        rest = tail;
+    }
+    if !rest.is_empty() {
+        return Err(USAGE.to_string());
+    }
+    let checks: Vec<_> = differential::checks()
+        .filter(|check| check.name.starts_with(prefix))
+        .collect();
+    if checks.is_empty() {
+        return Err(format!("no check starts with `{prefix}`"));
+    }
+
+    let mut failed = 0;
+    for check in checks {
+        match check.run(0..seeds, size) {
+            None => println!("{:<14} ok, {seeds} inputs", check.name),
+            Some(mismatch) => {
+                failed += 1;
+                println!("{:<14} {mismatch}", check.name);
+                println!(
+                    "reproduce with `gen {} --size {} --seed {}`:",
+                    check.day,
+                    size.unwrap_or(check.size),
+                    mismatch.seed
+                );
+                print!("{}", mismatch.input);
+                println!("minimized:");
+                print!("{}", minimize_check(check, &mismatch.input)?);
+            }
+        }
+    }
+    if failed > 0 {
+        Err(format!("{failed} checks disagree"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Test case of the smallest input `check` still disagrees on.
+fn minimize_check(check: &differential::Check, input: &str) -> Result<String, String> {
+    let shape = solution::find(check.day).map_or(Shape::Lines, |entry| entry.shape());
+    let failure = |input: &str| {
+        minimize::failure(|| match check.compare(input) {
+            Some(_) => Err(Error::puzzle("the solvers disagree")),
+            None => Ok(()),
+        })
+    };
+    let expected = failure(input).ok_or_else(|| format!("{} agrees on the input", check.name))?;
+    let input = minimize::minimize(input, shape, |input| {
+        failure(input).as_ref() == Some(&expected)
+    });
+    let (_, name) = check.name.split_once("::").unwrap_or(("", check.name));
+    Ok(minimize::test_case(
+        &format!("test_{name}_minimized"),
+        &input.unwrap_or_default(),
+        &format!(
+            "assert_eq!(crate::differential::find({:?}).unwrap().compare(input), None);",
+            check.name
+        ),
+    ))
+}
+
+fn minimize(args: &[String]) -> Result<(), String> {
+    let read = |path: &str| {
+        std::fs::read_to_string(path).map_err(|err| format!("failed to read {path}: {err}"))
+    };
+    let test = match args {
+        [flag, name, path] if flag == "--check" => {
+            let check =
+                differential::find(name).ok_or_else(|| format!("no check named `{name}`"))?;
+            minimize_check(check, &read(path)?)?
+        }
+        [day, part, path] => {
+            let day: u8 = day.parse().map_err(|_| format!("invalid day `{day}`"))?;
+            let part: u8 = part.parse().map_err(|_| format!("invalid part `{part}`"))?;
+            let entry = solution::find(day).ok_or_else(|| format!("day {day} not found"))?;
+            let failure = |input: &str| minimize::failure(|| entry.solve(part, input));
+            let input = read(path)?;
+            let expected =
+                failure(&input).ok_or_else(|| format!("day {day} part {part} solves {path}"))?;
+            eprintln!("minimizing while it fails with `{expected}`");
+            let input = minimize::minimize(&input, entry.shape(), |input| {
+                failure(input).as_ref() == Some(&expected)
+            });
+            minimize::test_case(
+                &format!("test_part{part}_minimized"),
+                &input.unwrap_or_default(),
+                &format!("assert!(crate::solve({day}, {part}, input).is_ok());"),
+            )
+        }
+        _ => return Err(USAGE.to_string()),
+    };
+    print!("{test}");
+    Ok(())
+}
+
+fn bench(args: &[String]) -> Result<(), String> {
+    let [day, rest @ ..] = args else {
+        return Err(USAGE.to_string());
+    };
+    let day: u8 = day.parse().map_err(|_| format!("invalid day `{day}`"))?;
+    let (parts, mut rest) = match rest {
+        [part, rest @ ..] if !part.starts_with("--") => {
+            let part: u8 = part.parse().map_err(|_| format!("invalid part `{part}`"))?;
+            (part..=part, rest)
+        }
+        _ => (1..=2, rest),
+    };
+    let (mut profile, mut options) = (DEFAULT_PROFILE, bench::Options::default());
+    let (mut compare, mut threshold) = (None, 10.0);
+    while let [flag, value, tail @ ..] = rest {
+        match flag.as_str() {
+            "--profile" => profile = value,
+            "--runs" => {
+                options.runs = value
+                    .parse()
+                    .map_err(|_| format!("invalid number of runs `{value}`"))?
+            }
+            "--compare" => compare = Some(value.as_str()),
+            "--threshold" => {
+                threshold = value
+                    .parse()
+                    .map_err(|_| format!("invalid threshold `{value}`"))?
+            }
+            _ => return Err(USAGE.to_string()),
+        }
+        rest = tail;
+    }
+    if !rest.is_empty() {
+        return Err(USAGE.to_string());
+    }
+    let entry = solution::find(day).ok_or_else(|| format!("day {day} is not implemented"))?;
+    let input = read_input(day, profile, None)?;
+    let commit = bench::current_commit().map_err(|err| format!("bench: {err}"))?;
+    let baseline = compare
+        .map(|rev| bench::commit(rev).map(|commit| (rev, commit)))
+        .transpose()
+        .map_err(|err| format!("bench: {err}"))?;
+    let history_path = &input::crate_root().join(bench::HISTORY);
+    let history =
+        bench::load_history(history_path).map_err(|err| format!("{}: {err}", bench::HISTORY))?;
+
+    println!(
+        "{:<20} {:>10} {:>10} {:>10} {:>10}",
+        "", "min", "median", "p95", "max"
+    );
+    let mut slower = 0;
+    for part in parts.clone() {
+        let report = entry
+            .bench(part, &input, &options)
+            .map_err(|err| format!("day {day} part {part}: {err}"))?;
+        let Some(report) = report else {
+            if parts.start() == parts.end() {
+                return Err(format!("day {day} part {part} is not implemented"));
+            }
+            continue;
+        };
+        let name = format!("day {day:02} part {part}");
+        for record in bench::Record::from_report(&commit, profile, day, part, &report) {
+            print!("{name:<14} {} {}", record.phase, record.stats);
+            if let Some((rev, baseline)) = &baseline {
+                match record.baseline(&history, baseline) {
+                    Some(then) => {
+                        let change = record.change(then);
+                        print!(" {change:>+7.1}% vs {rev}");
+                        if change > threshold {
+                            slower += 1;
+                            print!(" SLOWER");
+                        }
+                    }
+                    None => print!(" no baseline at {rev}"),
+                }
+            }
+            println!();
+            bench::append_history(history_path, &record)
+                .map_err(|err| format!("{}: {err}", bench::HISTORY))?;
+        }
+        eprintln!("{name}: {} ({} runs)", report.answer, report.runs);
+    }
+
+    if slower > 0 {
+        Err(format!("{slower} phases got more than {threshold}% slower"))
+    } else {
+        Ok(())
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("run") => run(&args[1..]),
+        Some("fetch") => fetch(&args[1..]),
+        Some("submit") => submit(&args[1..]),
+        Some("bench") => bench(&args[1..]),
+        Some("verify") => verify(&args[1..]),
+        Some("new") => new(&args[1..]),
+        Some("gen") => generate(&args[1..]),
+        Some("diff") => diff(&args[1..]),
+        Some("minimize") => minimize(&args[1..]),
+        _ => Err(USAGE.to_string()),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
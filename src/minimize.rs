@@ -0,0 +1,240 @@
+use std::{
+    fmt::Write,
+    panic::{self, AssertUnwindSafe},
+};
+
+use crate::error::{Error, Result};
+
+/// How an input comes apart into pieces the minimizer can drop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shape {
+    /// One record per line.
+    Lines,
+    /// Records between a delimiter, like the comma-separated ranges of day 2.
+    Records(char),
+    /// A grid of characters, losing whole rows and whole columns.
+    Grid,
+}
+
+/// Smallest subsequence of `items` found by delta debugging (ddmin) for which `fails`
+/// still holds, assuming it holds for all of `items`.
+///
+/// Drops ever smaller chunks until no single item can go, so the result is 1-minimal:
+/// removing any one of its items makes `fails` false.
+pub fn ddmin<T: Clone>(mut items: Vec<T>, mut fails: impl FnMut(&[T]) -> bool) -> Vec<T> {
+    let mut chunks = 2;
+    while items.len() >= 2 {
+        let size = items.len().div_ceil(chunks);
+        let reduced = (0..items.len()).step_by(size).find_map(|start| {
+            let rest: Vec<_> = items[..start]
+                .iter()
+                .chain(items.get(start + size..).unwrap_or_default())
+                .cloned()
+                .collect();
+            fails(&rest).then_some(rest)
+        });
+        match reduced {
+            Some(rest) => {
+                items = rest;
+                chunks = (chunks - 1).max(2);
+            }
+            None if chunks >= items.len() => break,
+            None => chunks = (chunks * 2).min(items.len()),
+        }
+    }
+    // a single item may still be more than needed
+    if items.len() == 1 && fails(&[]) {
+        items.clear();
+    }
+    items
+}
+
+type Hook = Box<dyn Fn(&panic::PanicHookInfo<'_>) + Sync + Send>;
+
+/// Panic hook in place before [`QuietPanics`] silenced it, put back on drop.
+struct QuietPanics(Option<Hook>);
+
+impl QuietPanics {
+    fn new() -> Self {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        Self(Some(previous))
+    }
+}
+
+impl Drop for QuietPanics {
+    fn drop(&mut self) {
+        if let Some(previous) = self.0.take() {
+            panic::set_hook(previous);
+        }
+    }
+}
+
+/// Shrinks `input` by dropping the pieces of `shape` while `fails` holds, `None` if it
+/// doesn't hold for `input` to begin with.
+///
+/// The panics [`failure`] catches along the way are kept off stderr.
+pub fn minimize(input: &str, shape: Shape, mut fails: impl FnMut(&str) -> bool) -> Option<String> {
+    if !fails(input) {
+        return None;
+    }
+    let _quiet = QuietPanics::new();
+    let newline = if input.ends_with('\n') { "\n" } else { "" };
+    let body = input.strip_suffix('\n').unwrap_or(input);
+    let minimal = match shape {
+        Shape::Lines => {
+            let lines: Vec<&str> = body.split('\n').collect();
+            let join = |lines: &[&str]| lines.join("\n") + newline;
+            join(&ddmin(lines, |lines| fails(&join(lines))))
+        }
+        Shape::Records(delimiter) => {
+            let records: Vec<&str> = body.split(delimiter).collect();
+            let join = |records: &[&str]| records.join(&delimiter.to_string()) + newline;
+            join(&ddmin(records, |records| fails(&join(records))))
+        }
+        Shape::Grid => {
+            let mut rows: Vec<Vec<char>> =
+                body.split('\n').map(|row| row.chars().collect()).collect();
+            let join = |rows: &[Vec<char>]| {
+                let rows: Vec<String> = rows.iter().map(|row| row.iter().collect()).collect();
+                rows.join("\n") + newline
+            };
+            // dropping columns can make more rows removable and the other way round
+            loop {
+                let before = (rows.len(), rows.iter().map(Vec::len).max());
+                rows = ddmin(rows, |rows| fails(&join(rows)));
+                let width = rows.iter().map(Vec::len).max().unwrap_or_default();
+                let keep = |columns: &[usize]| -> Vec<Vec<char>> {
+                    rows.iter()
+                        .map(|row| {
+                            columns
+                                .iter()
+                                .filter_map(|&x| row.get(x).copied())
+                                .collect()
+                        })
+                        .collect()
+                };
+                let columns = ddmin((0..width).collect(), |columns| fails(&join(&keep(columns))));
+                rows = keep(&columns);
+                if (rows.len(), rows.iter().map(Vec::len).max()) == before {
+                    break;
+                }
+            }
+            join(&rows)
+        }
+    };
+    Some(minimal)
+}
+
+/// How `solve` fails, `None` if it doesn't: the error without its position, which
+/// moves as lines go, or the message of a panic.
+///
+/// Minimizing keeps inputs that fail the same way, or a broken line would soon give
+/// way to an empty input that fails for a reason of its own. Panics still go to the
+/// panic hook, [`minimize`] silences it while it shrinks.
+pub fn failure<T>(solve: impl FnOnce() -> Result<T>) -> Option<String> {
+    match panic::catch_unwind(AssertUnwindSafe(solve)) {
+        Ok(Ok(_)) => None,
+        Ok(Err(Error::Parse { message, .. })) => Some(message),
+        Ok(Err(err)) => Some(err.to_string()),
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            Some(format!("panicked: {message}"))
+        }
+    }
+}
+
+/// `input` as a Rust string literal, with its lines on lines of their own as the tests
+/// of this crate write them.
+fn literal(input: &str) -> String {
+    let escaped = input.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{escaped}\"")
+}
+
+/// A `#[test]` named `name` for a day's test module, asserting `check` on `input`.
+///
+/// `check` is the body's last statement and refers to the input as `input`.
+pub fn test_case(name: &str, input: &str, check: &str) -> String {
+    let mut test = String::new();
+    writeln!(test, "    #[test]").unwrap();
+    writeln!(test, "    fn {name}() {{").unwrap();
+    writeln!(test, "        let input = {};", literal(input)).unwrap();
+    writeln!(test, "        {check}").unwrap();
+    writeln!(test, "    }}").unwrap();
+    test
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ddmin() {
+        let items: Vec<u32> = (0..40).collect();
+        let needs =
+            |items: &[u32]| items.contains(&3) && items.contains(&17) && items.contains(&38);
+        assert_eq!(ddmin(items.clone(), needs), vec![3, 17, 38]);
+        assert_eq!(ddmin(items, |_| true), Vec::<u32>::new());
+        assert_eq!(ddmin(vec![5], |items| items == [5]), vec![5]);
+    }
+
+    #[test]
+    fn test_minimize() {
+        let lines = "L1\nR5\nL68\nR2\nL30\n";
+        let fails = |input: &str| input.contains("68");
+        assert_eq!(minimize(lines, Shape::Lines, fails).unwrap(), "L68\n");
+        assert!(minimize(lines, Shape::Lines, |input| input.contains("99")).is_none());
+
+        let ranges = "1-2,11-22,95-115";
+        let fails = |input: &str| input.contains("11-22");
+        assert_eq!(
+            minimize(ranges, Shape::Records(','), fails).unwrap(),
+            "11-22"
+        );
+
+        let grid = "..@..\n.....\n@.@.@\n.....";
+        let fails = |input: &str| input.lines().any(|row| row.matches('@').count() >= 2);
+        assert_eq!(minimize(grid, Shape::Grid, fails).unwrap(), "@@");
+    }
+
+    #[test]
+    fn test_failure() {
+        assert_eq!(failure(|| Ok(1)), None);
+        let input = "L1\nLx\n";
+        let parse = |input: &str| failure(|| crate::solve(1, 1, input));
+        assert_eq!(parse(input).unwrap(), "expected a number, found `x`");
+        assert_eq!(
+            minimize(input, Shape::Lines, |input| parse(input) == parse("Lx")).unwrap(),
+            "Lx\n"
+        );
+        let panics = failure::<()>(|| panic!("at {}", 3));
+        assert_eq!(panics.unwrap(), "panicked: at 3");
+        let explode = |input: &str| {
+            failure(|| {
+                assert!(!input.contains('c'), "found c");
+                Ok(())
+            })
+        };
+        let shrunk = minimize("ab\nc\nd\n", Shape::Lines, |input| explode(input).is_some());
+        assert_eq!(shrunk.unwrap(), "c\n");
+    }
+
+    #[test]
+    fn test_test_case() {
+        let test = test_case("test_small", "1,\"2\"\n3\\4", "assert!(input.is_empty());");
+        assert_eq!(
+            test,
+            r#"    #[test]
+    fn test_small() {
+        let input = "1,\"2\"
+3\\4";
+        assert!(input.is_empty());
+    }
+"#
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use std::{
+    num::NonZeroUsize,
+    panic,
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+};
+
+/// `jobs` as a worker count, `0` is one per available core.
+pub fn workers(jobs: usize) -> usize {
+    match jobs {
+        0 => thread::available_parallelism().map_or(1, NonZeroUsize::get),
+        jobs => jobs,
+    }
+}
+
+/// `f` applied to every item with its index, on up to `jobs` scoped threads.
+///
+/// Workers take the next unclaimed item so uneven items balance out, the results
+/// are in the order of `items` whatever order they finish in. The caller's `tracing`
+/// subscriber and span carry over to the workers.
+pub fn map<T, R>(items: &[T], jobs: usize, f: impl Fn(usize, &T) -> R + Sync) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+{
+    let jobs = jobs.min(items.len());
+    if jobs <= 1 {
+        return items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| f(i, item))
+            .collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(items.len()));
+    let dispatch = tracing::dispatcher::get_default(Clone::clone);
+    let span = tracing::Span::current();
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs)
+            .map(|_| {
+                scope.spawn(|| {
+                    tracing::dispatcher::with_default(&dispatch, || {
+                        span.in_scope(|| {
+                            loop {
+                                let i = next.fetch_add(1, Ordering::Relaxed);
+                                let Some(item) = items.get(i) else { break };
+                                let result = f(i, item);
+                                results.lock().unwrap().push((i, result));
+                            }
+                        })
+                    })
+                })
+            })
+            .collect();
+        for worker in workers {
+            if let Err(payload) = worker.join() {
+                panic::resume_unwind(payload);
+            }
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_unstable_by_key(|&(i, _)| i);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_map() {
+        let items: Vec<u64> = (0..100).collect();
+        let square = |i: usize, &n: &u64| {
+            assert_eq!(i as u64, n);
+            n * n
+        };
+        let serial = map(&items, 1, square);
+        assert_eq!(serial, items.iter().map(|n| n * n).collect::<Vec<_>>());
+        for jobs in [2, 3, 8, 200] {
+            assert_eq!(map(&items, jobs, square), serial);
+        }
+        assert!(map(&[] as &[u64], 4, square).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "item 7")]
+    fn test_map_panic() {
+        let items: Vec<usize> = (0..10).collect();
+        map(&items, 4, |_, &n| assert_ne!(n, 7, "item {n}"));
+    }
+}
@@ -0,0 +1,319 @@
+use std::{
+    fmt::{self, Write},
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::{
+    answer::Answer,
+    error::Result,
+    input::{self, Profile},
+    parallel,
+    solution::{self, Day},
+    verify::{Expected, load_manifest},
+};
+
+/// How an answer compares to the manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail,
+    /// The manifest has no answer for the part.
+    Unknown,
+    /// The profile has no input for the day.
+    Missing,
+    Error(String),
+}
+
+/// One part of a day run once on `dayNN.txt` of a profile.
+#[derive(Debug, Clone)]
+pub struct Row {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Option<Answer>,
+    pub expected: Option<Answer>,
+    pub status: Status,
+    pub parse: Option<Duration>,
+    pub solve: Option<Duration>,
+    /// [`hash`] of the input.
+    pub input_hash: Option<u64>,
+}
+
+/// 64-bit FNV-1a of `input`, the same on every platform and release.
+pub fn hash(input: &str) -> u64 {
+    input.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Runs every part of every registered day on the inputs of `profile`, `jobs` days
+/// at a time (see [`parallel::workers`]). Rows are in day order either way.
+pub fn run_all(profile: &Profile, jobs: usize) -> Result<Vec<Row>> {
+    let manifest = load_manifest(&profile.dir)?;
+    let days = parallel::map(solution::DAYS, parallel::workers(jobs), |_, entry| {
+        run_day(profile, &manifest, *entry)
+    });
+    Ok(days.into_iter().collect::<Result<Vec<_>>>()?.concat())
+}
+
+fn run_day(profile: &Profile, manifest: &[Expected], entry: &dyn Day) -> Result<Vec<Row>> {
+    let mut rows = vec![];
+    let day = entry.day();
+    let file = format!("day{day:02}.txt");
+    let text = input::read(&profile.dir.join(&file))?;
+    for part in 1..=2 {
+        let expected = manifest.iter().find(|expected| {
+            (expected.day, expected.part) == (day, part) && expected.input == file
+        });
+        let settings = expected.map_or(&[][..], |expected| &expected.settings);
+        let mut row = Row {
+            day,
+            part,
+            answer: None,
+            expected: expected.map(|expected| expected.answer.clone()),
+            status: Status::Missing,
+            parse: None,
+            solve: None,
+            input_hash: None,
+        };
+        if let Some(text) = &text {
+            row.input_hash = Some(hash(text));
+            match entry.time(part, text, settings) {
+                Ok(None) => continue,
+                Ok(Some(timed)) => {
+                    row.status = match &row.expected {
+                        Some(expected) if *expected == timed.answer => Status::Pass,
+                        Some(_) => Status::Fail,
+                        None => Status::Unknown,
+                    };
+                    row.answer = Some(timed.answer);
+                    row.parse = Some(timed.parse);
+                    row.solve = Some(timed.solve);
+                }
+                Err(err) => row.status = Status::Error(err.to_string()),
+            }
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// Document `run --all --report` writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Markdown,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Format::Json),
+            "md" => Ok(Format::Markdown),
+            _ => Err(format!("unknown report format `{s}`")),
+        }
+    }
+}
+
+fn json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn json_option(out: &mut String, value: Option<impl fmt::Display>) {
+    match value {
+        Some(value) => json_string(out, &value.to_string()),
+        None => out.push_str("null"),
+    }
+}
+
+/// `{"profile": ..., "results": [...]}` with answers as strings and times in nanoseconds.
+pub fn json(profile: &str, rows: &[Row]) -> String {
+    let mut out = String::from("{\n  \"profile\": ");
+    json_string(&mut out, profile);
+    out.push_str(",\n  \"results\": [");
+    for (i, row) in rows.iter().enumerate() {
+        out.push_str(if i == 0 { "\n" } else { ",\n" });
+        write!(
+            out,
+            "    {{\"day\": {}, \"part\": {}, \"answer\": ",
+            row.day, row.part
+        )
+        .unwrap();
+        json_option(&mut out, row.answer.as_ref());
+        out.push_str(", \"expected\": ");
+        json_option(&mut out, row.expected.as_ref());
+        out.push_str(", \"status\": ");
+        json_string(&mut out, &row.status.to_string());
+        for (name, time) in [("parse_ns", row.parse), ("solve_ns", row.solve)] {
+            match time {
+                Some(time) => write!(out, ", \"{name}\": {}", time.as_nanos()).unwrap(),
+                None => write!(out, ", \"{name}\": null").unwrap(),
+            }
+        }
+        out.push_str(", \"input_hash\": ");
+        json_option(&mut out, row.input_hash.map(|hash| format!("{hash:016x}")));
+        out.push('}');
+    }
+    out.push_str("\n  ]\n}\n");
+    out
+}
+
+/// Table with one row per part, ready to paste into the README.
+pub fn markdown(rows: &[Row]) -> String {
+    let mut out = String::from(
+        "| Day | Part | Answer | Expected | Status | Parse | Solve | Input |\n\
+         |----:|-----:|--------|----------|--------|------:|------:|-------|\n",
+    );
+    let cell = |value: Option<String>| match value {
+        Some(value) => value.replace('|', "\\|"),
+        None => "-".to_string(),
+    };
+    for row in rows {
+        writeln!(
+            out,
+            "| {} | {} | {} | {} | {} | {} | {} | {} |",
+            row.day,
+            row.part,
+            cell(row.answer.as_ref().map(Answer::to_string)),
+            cell(row.expected.as_ref().map(Answer::to_string)),
+            cell(Some(row.status.to_string())),
+            cell(row.parse.map(|time| format!("{time:.2?}"))),
+            cell(row.solve.map(|time| format!("{time:.2?}"))),
+            cell(row.input_hash.map(|hash| format!("`{hash:016x}`"))),
+        )
+        .unwrap();
+    }
+    out
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail => write!(f, "FAIL"),
+            Status::Unknown => write!(f, "unknown"),
+            Status::Missing => write!(f, "missing"),
+            Status::Error(err) => write!(f, "ERROR {err}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::input::Scratch;
+
+    #[test]
+    fn test_hash() {
+        assert_eq!(hash(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(hash("a"), 0xaf63_dc4c_8601_ec8c);
+        assert_ne!(hash("L68\nL30"), hash("L68\nL31"));
+    }
+
+    #[test]
+    fn test_run_all() {
+        let dir = Scratch::new("report");
+        std::fs::write(
+            dir.join("day01.txt"),
+            "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82",
+        )
+        .unwrap();
+        std::fs::write(dir.join("day02.txt"), "11-x").unwrap();
+        std::fs::write(
+            dir.join("answers.txt"),
+            "1 1 day01.txt 3\n1 2 day01.txt 7\n",
+        )
+        .unwrap();
+        let rows = run_all(&Profile::new(&dir, "default"), 1).unwrap();
+        let parallel = run_all(&Profile::new(&dir, "default"), 4).unwrap();
+
+        let outcome = |rows: &[Row]| -> Vec<_> {
+            rows.iter()
+                .map(|row| (row.day, row.part, row.answer.clone(), row.status.clone()))
+                .collect()
+        };
+        assert_eq!(outcome(&parallel), outcome(&rows));
+
+        let status: Vec<_> = rows
+            .iter()
+            .take(5)
+            .map(|row| (row.day, row.part, row.status.to_string()))
+            .collect();
+        assert_eq!(
+            status,
+            vec![
+                (1, 1, "pass".to_string()),
+                (1, 2, "FAIL".to_string()),
+                (2, 1, "ERROR 1:4: expected a number, found `x`".to_string()),
+                (2, 2, "ERROR 1:4: expected a number, found `x`".to_string()),
+                (3, 1, "missing".to_string()),
+            ]
+        );
+        assert_eq!(rows[1].answer, Some(6.into()));
+        assert_eq!(rows[1].expected, Some(7.into()));
+        assert!(rows[1].parse.is_some() && rows[1].solve.is_some());
+        assert_eq!(rows[2].input_hash, Some(hash("11-x")));
+        assert_eq!(rows[4].input_hash, None);
+    }
+
+    #[test]
+    fn test_render() {
+        let rows = [
+            Row {
+                day: 1,
+                part: 2,
+                answer: Some(6.into()),
+                expected: None,
+                status: Status::Unknown,
+                parse: Some(Duration::from_micros(12)),
+                solve: Some(Duration::from_nanos(3400)),
+                input_hash: Some(0xab),
+            },
+            Row {
+                day: 12,
+                part: 1,
+                answer: None,
+                expected: Some("a|\"b\"".into()),
+                status: Status::Missing,
+                parse: None,
+                solve: None,
+                input_hash: None,
+            },
+        ];
+        assert_eq!(
+            json("alice", &rows),
+            r#"{
+  "profile": "alice",
+  "results": [
+    {"day": 1, "part": 2, "answer": "6", "expected": null, "status": "unknown", "parse_ns": 12000, "solve_ns": 3400, "input_hash": "00000000000000ab"},
+    {"day": 12, "part": 1, "answer": null, "expected": "a|\"b\"", "status": "missing", "parse_ns": null, "solve_ns": null, "input_hash": null}
+  ]
+}
+"#
+        );
+        assert_eq!(
+            json("default", &[]),
+            "{\n  \"profile\": \"default\",\n  \"results\": [\n  ]\n}\n"
+        );
+        assert_eq!(
+            markdown(&rows),
+            "| Day | Part | Answer | Expected | Status | Parse | Solve | Input |
+|----:|-----:|--------|----------|--------|------:|------:|-------|
+| 1 | 2 | 6 | - | unknown | 12.00µs | 3.40µs | `00000000000000ab` |
+| 12 | 1 | - | a\\|\"b\" | missing | - | - | - |
+"
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    error::{Error, Result},
+    input::{DEFAULT_PROFILE, INPUT_DIR, Profile},
+};
+
+/// Module of a new day, `{{DAY}}` is replaced by the day and `{{NN}}` by it zero-padded.
+const TEMPLATE: &str = include_str!("../templates/day.rs");
+
+/// Writes the module of `day` from the template into the crate in `dir`, where
+/// build.rs picks it up, along with an empty input for the default profile to paste
+/// into. Refuses to overwrite an existing day, and keeps an input that is already there.
+pub fn scaffold(dir: &Path, day: u8) -> Result<PathBuf> {
+    if !(1..=25).contains(&day) {
+        return Err(Error::puzzle(format!("there is no day {day}")));
+    }
+    let path = dir.join(format!("src/day{day:02}.rs"));
+    let module = TEMPLATE
+        .replace("{{NN}}", &format!("{day:02}"))
+        .replace("{{DAY}}", &day.to_string());
+    match File::create_new(&path) {
+        Ok(mut file) => file.write_all(module.as_bytes())?,
+        Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+            return Err(Error::Refused(format!("day {day} already exists")));
+        }
+        Err(err) => return Err(err.into()),
+    }
+    let profile = Profile::new(&dir.join(INPUT_DIR), DEFAULT_PROFILE);
+    std::fs::create_dir_all(&profile.dir)?;
+    match File::create_new(profile.day_path(day)) {
+        Err(err) if err.kind() != io::ErrorKind::AlreadyExists => return Err(err.into()),
+        _ => {}
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::input::Scratch;
+
+    #[test]
+    fn test_scaffold() {
+        let dir = Scratch::new("scaffold");
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/day05.rs"), "// solved").unwrap();
+        std::fs::create_dir_all(dir.join("input")).unwrap();
+        std::fs::write(dir.join("input/day14.txt"), "fetched").unwrap();
+
+        let written = scaffold(&dir, 13);
+        let module = std::fs::read_to_string(dir.join("src/day13.rs"));
+        let input = std::fs::read_to_string(dir.join("input/day13.txt"));
+        let again = scaffold(&dir, 13);
+        let existing = scaffold(&dir, 5);
+        let kept = std::fs::read_to_string(dir.join("src/day05.rs"));
+        let fetched = scaffold(&dir, 14);
+
+        assert_eq!(written.unwrap(), dir.join("src/day13.rs"));
+        let module = module.unwrap();
+        assert!(module.contains("pub struct Day13;"));
+        assert!(module.contains("const DAY: u8 = 13;"));
+        assert!(module.contains("assert_answers(13, 2,"));
+        assert_eq!(input.unwrap(), "");
+        assert_eq!(kept.unwrap(), "// solved");
+        assert!(!dir.join("input/day05.txt").exists());
+        for refused in [again, existing] {
+            assert!(matches!(refused, Err(Error::Refused(_))), "{refused:?}");
+        }
+        assert_eq!(fetched.unwrap(), dir.join("src/day14.rs"));
+        let input = std::fs::read_to_string(dir.join("input/day14.txt"));
+        assert_eq!(input.unwrap(), "fetched");
+        assert!(scaffold(&dir, 26).is_err());
+    }
+}
@@ -0,0 +1,270 @@
+use std::{io::BufRead, marker::PhantomData};
+
+use crate::{
+    answer::Answer,
+    bench::{self, Report, Timed},
+    differential::Check,
+    error::{Error, Result},
+    generator::Rng,
+    minimize::Shape,
+    parallel,
+};
+
+/// A day's puzzle split into a parse phase and the two solve phases.
+pub trait Solution {
+    const DAY: u8;
+
+    /// Parsed puzzle input shared by both parts.
+    type Input<'a>;
+
+    /// Puzzle parameters that differ between the example and the real input.
+    type Config: Default;
+
+    /// Pieces the minimizer may drop from an input.
+    const SHAPE: Shape = Shape::Lines;
+
+    /// Optimized solvers of the day checked against references, see [`crate::differential`].
+    const CHECKS: &'static [Check] = &[];
+
+    /// `config` with the work split between `jobs` workers, as it is for days that
+    /// can't split it.
+    fn with_jobs(config: Self::Config, _jobs: usize) -> Self::Config {
+        config
+    }
+
+    /// `config` with the setting `name` changed to `value`, as a manifest line such as
+    /// `8 1 day08.txt 40 connections=10` asks for. Days without settings refuse them all.
+    fn with_setting(_config: Self::Config, name: &str, _value: &str) -> Result<Self::Config> {
+        Err(Error::puzzle(format!(
+            "day {} has no setting `{name}`",
+            Self::DAY
+        )))
+    }
+
+    fn parse(input: &str) -> Result<Self::Input<'_>>;
+
+    fn part1(input: &Self::Input<'_>, config: &Self::Config) -> Result<Answer>;
+
+    /// Returns `None` for days without a second part.
+    fn part2(_input: &Self::Input<'_>, _config: &Self::Config) -> Result<Option<Answer>> {
+        Ok(None)
+    }
+
+    /// Runs `part` on input read from `reader`, `None` if the part doesn't exist.
+    ///
+    /// Reads all of it before parsing, line-oriented days override this to go
+    /// through it a record at a time.
+    fn stream(part: u8, reader: &mut dyn BufRead, config: &Self::Config) -> Result<Option<Answer>> {
+        let mut input = String::new();
+        reader.read_to_string(&mut input)?;
+        let input = Self::parse(&input)?;
+        match part {
+            1 => Self::part1(&input, config).map(Some),
+            2 => Self::part2(&input, config),
+            _ => Ok(None),
+        }
+    }
+
+    /// A random valid input with about `size` records (lines, ranges, boxes, machines,
+    /// ...), or a grid about `size` wide. `None` for days without a generator.
+    fn generate(_rng: &mut Rng, _size: usize) -> Option<String> {
+        None
+    }
+}
+
+/// Object-safe view of a [`Solution`] used by the registry.
+pub trait Day: Sync {
+    fn day(&self) -> u8;
+
+    /// Runs `part` with the default config, `None` if the part doesn't exist.
+    fn solve(&self, part: u8, input: &str) -> Result<Option<Answer>>;
+
+    /// [`Day::solve`] on `jobs` workers, see [`parallel::workers`].
+    fn solve_with(&self, part: u8, input: &str, jobs: usize) -> Result<Option<Answer>>;
+
+    /// [`Day::solve`] with `settings` applied in order, see [`Solution::with_setting`].
+    fn solve_setting(
+        &self,
+        part: u8,
+        input: &str,
+        settings: &[(String, String)],
+    ) -> Result<Option<Answer>>;
+
+    /// Runs `part` on input read from `reader`, see [`Solution::stream`].
+    fn stream(&self, part: u8, reader: &mut dyn BufRead) -> Result<Option<Answer>>;
+
+    /// [`Day::stream`] on `jobs` workers, see [`parallel::workers`].
+    fn stream_with(
+        &self,
+        part: u8,
+        reader: &mut dyn BufRead,
+        jobs: usize,
+    ) -> Result<Option<Answer>>;
+
+    /// Runs `part` once with `settings` applied, timing each phase, `None` if the part
+    /// doesn't exist.
+    fn time(&self, part: u8, input: &str, settings: &[(String, String)]) -> Result<Option<Timed>>;
+
+    /// Times the parse and solve phases of `part`, `None` if the part doesn't exist.
+    fn bench(&self, part: u8, input: &str, options: &bench::Options) -> Result<Option<Report>>;
+
+    /// Input from [`Solution::generate`] seeded with `seed`.
+    fn generate(&self, size: usize, seed: u64) -> Option<String>;
+
+    /// [`Solution::SHAPE`].
+    fn shape(&self) -> Shape;
+
+    /// [`Solution::CHECKS`].
+    fn checks(&self) -> &'static [Check];
+}
+
+struct Entry<S>(PhantomData<fn() -> S>);
+
+impl<S> Entry<S> {
+    const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<S: Solution> Entry<S> {
+    fn config(jobs: usize) -> S::Config {
+        S::with_jobs(S::Config::default(), parallel::workers(jobs))
+    }
+
+    fn settings_config(settings: &[(String, String)]) -> Result<S::Config> {
+        settings
+            .iter()
+            .try_fold(S::Config::default(), |config, (name, value)| {
+                S::with_setting(config, name, value)
+            })
+    }
+
+    fn solve_config(part: u8, input: &str, config: &S::Config) -> Result<Option<Answer>> {
+        match part {
+            1 => S::part1(&S::parse(input)?, config).map(Some),
+            2 => S::part2(&S::parse(input)?, config),
+            _ => Ok(None),
+        }
+    }
+}
+
+impl<S: Solution> Day for Entry<S> {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn solve(&self, part: u8, input: &str) -> Result<Option<Answer>> {
+        Self::solve_config(part, input, &S::Config::default())
+    }
+
+    fn solve_with(&self, part: u8, input: &str, jobs: usize) -> Result<Option<Answer>> {
+        Self::solve_config(part, input, &Self::config(jobs))
+    }
+
+    fn solve_setting(
+        &self,
+        part: u8,
+        input: &str,
+        settings: &[(String, String)],
+    ) -> Result<Option<Answer>> {
+        Self::solve_config(part, input, &Self::settings_config(settings)?)
+    }
+
+    fn stream(&self, part: u8, reader: &mut dyn BufRead) -> Result<Option<Answer>> {
+        S::stream(part, reader, &S::Config::default())
+    }
+
+    fn stream_with(
+        &self,
+        part: u8,
+        reader: &mut dyn BufRead,
+        jobs: usize,
+    ) -> Result<Option<Answer>> {
+        S::stream(part, reader, &Self::config(jobs))
+    }
+
+    fn time(&self, part: u8, input: &str, settings: &[(String, String)]) -> Result<Option<Timed>> {
+        let config = Self::settings_config(settings)?;
+        match part {
+            1 => bench::time(
+                || S::parse(input),
+                |parsed| S::part1(parsed, &config).map(Some),
+            ),
+            2 => bench::time(|| S::parse(input), |parsed| S::part2(parsed, &config)),
+            _ => Ok(None),
+        }
+    }
+
+    fn bench(&self, part: u8, input: &str, options: &bench::Options) -> Result<Option<Report>> {
+        let config = S::Config::default();
+        let solve = |parsed: &S::Input<'_>| match part {
+            1 => S::part1(parsed, &config).map(Some),
+            2 => S::part2(parsed, &config),
+            _ => Ok(None),
+        };
+        bench::measure(options, || S::parse(input), solve)
+    }
+
+    fn generate(&self, size: usize, seed: u64) -> Option<String> {
+        S::generate(&mut Rng::new(seed), size)
+    }
+
+    fn shape(&self) -> Shape {
+        S::SHAPE
+    }
+
+    fn checks(&self) -> &'static [Check] {
+        S::CHECKS
+    }
+}
+
+// `DAYS`, every `src/dayNN.rs` in order, see build.rs.
+include!(concat!(env!("OUT_DIR"), "/registry.rs"));
+
+pub fn find(day: u8) -> Option<&'static dyn Day> {
+    DAYS.iter().copied().find(|entry| entry.day() == day)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_registry_order() {
+        let days: Vec<_> = DAYS.iter().map(|day| day.day()).collect();
+        assert_eq!(days, (1..=12).collect::<Vec<_>>());
+        assert!(find(13).is_none());
+    }
+
+    #[test]
+    fn test_solve() {
+        let input = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82";
+        assert_eq!(find(1).unwrap().solve(2, input).unwrap(), Some(6.into()));
+        assert_eq!(find(1).unwrap().solve(3, input).unwrap(), None);
+        assert!(find(1).unwrap().solve(1, "L1\nX2").is_err());
+        let ranges = "11-22,95-115,998-1012,1188511880-1188511890";
+        for jobs in [0, 1, 3] {
+            let day = find(2).unwrap();
+            assert_eq!(
+                day.solve_with(1, ranges, jobs).unwrap(),
+                day.solve(1, ranges).unwrap()
+            );
+        }
+        let settings = |name: &str, value: &str| [(name.to_string(), value.to_string())];
+        let boxes = "0,0,0\n1,0,0\n5,0,0\n7,0,0";
+        let day = find(8).unwrap();
+        assert_eq!(
+            day.solve_setting(1, boxes, &settings("connections", "1"))
+                .unwrap(),
+            Some(2.into())
+        );
+        let err = day.solve_setting(1, boxes, &settings("connections", "x"));
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "setting `connections` expects a count, got `x`"
+        );
+        let day = find(1).unwrap();
+        let err = day.solve_setting(1, input, &settings("dial", "50"));
+        assert_eq!(err.unwrap_err().to_string(), "day 1 has no setting `dial`");
+    }
+}
@@ -0,0 +1,128 @@
+use std::io::{self, BufRead};
+
+use crate::error::{Error, Result};
+
+/// One record of a stream with the 1-based position it starts at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub text: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Record {
+    /// Runs `parse` on the text as if it were the whole input, moving its errors to
+    /// where the record is in the stream.
+    pub fn parse<T>(&self, parse: impl FnOnce(&str) -> Result<T>) -> Result<T> {
+        parse(&self.text).map_err(|err| err.shifted(self.line, self.column))
+    }
+}
+
+/// Reads `reader` one record at a time, see [`lines`] and [`records`].
+pub struct Records<R> {
+    reader: R,
+    delimiter: u8,
+    line: usize,
+    column: usize,
+    buf: Vec<u8>,
+}
+
+/// Lines of `reader` without their `\n` or `\r\n`, like [`str::lines`].
+pub fn lines<R: BufRead>(reader: R) -> Records<R> {
+    records(reader, b'\n')
+}
+
+/// Parts of `reader` between `delimiter`s, without the delimiter.
+pub fn records<R: BufRead>(reader: R, delimiter: u8) -> Records<R> {
+    Records {
+        reader,
+        delimiter,
+        line: 1,
+        column: 1,
+        buf: vec![],
+    }
+}
+
+impl<R: BufRead> Iterator for Records<R> {
+    type Item = Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buf.clear();
+        match self.reader.read_until(self.delimiter, &mut self.buf) {
+            Ok(0) => return None,
+            Ok(_) => {}
+            Err(err) => return Some(Err(err.into())),
+        }
+        let (line, column) = (self.line, self.column);
+        let text = match std::str::from_utf8(&self.buf) {
+            Ok(text) => text,
+            Err(err) => {
+                return Some(Err(Error::Io(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{line}:{column}: {err}"),
+                ))));
+            }
+        };
+        for c in text.chars() {
+            if c == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+        let mut text = text.strip_suffix(self.delimiter as char).unwrap_or(text);
+        if self.delimiter == b'\n' {
+            text = text.strip_suffix('\r').unwrap_or(text);
+        }
+        Some(Ok(Record {
+            text: text.to_string(),
+            line,
+            column,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_lines() {
+        let records: Vec<_> = lines("ab\r\n\ncd".as_bytes())
+            .map(|record| {
+                let record = record.unwrap();
+                (record.text, record.line, record.column)
+            })
+            .collect();
+        assert_eq!(
+            records,
+            vec![
+                ("ab".to_string(), 1, 1),
+                (String::new(), 2, 1),
+                ("cd".to_string(), 3, 1),
+            ]
+        );
+        assert!(lines(&[b'a', 0xff, b'\n'][..]).next().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_records() {
+        let mut records = records("1-2,3-\n4,5-x".as_bytes(), b',');
+        let first = records.next().unwrap().unwrap();
+        assert_eq!(
+            (first.text.as_str(), first.line, first.column),
+            ("1-2", 1, 1)
+        );
+        let second = records.next().unwrap().unwrap();
+        assert_eq!((second.text.as_str(), second.column), ("3-\n4", 5));
+        let third = records.next().unwrap().unwrap();
+        assert_eq!((third.line, third.column), (2, 3));
+        assert!(records.next().is_none());
+
+        let err = third
+            .parse(|text| crate::error::parse_at::<u8>(text, &text[2..], "a number"))
+            .unwrap_err();
+        assert_eq!(err.to_string(), "2:5: expected a number, found `x`");
+    }
+}
@@ -0,0 +1,440 @@
+use std::{fmt, path::Path, time::Duration};
+
+use tracing::info;
+
+use crate::{
+    answer::Answer,
+    error::{Error, Result, parse_at},
+    fetch::{Fetcher, now},
+    input::{self, Profile},
+    verify::{self, Expected},
+};
+
+/// History of submitted answers, inside each profile directory.
+pub const HISTORY: &str = "submissions.txt";
+
+/// File in the profile directory holding the earliest time for the next submission.
+const COOLDOWN_FILE: &str = ".next-submit";
+
+/// What the site said about a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// Submitted too soon after the previous answer.
+    Wait(Duration),
+    /// The part was solved before, so the answer wasn't checked.
+    AlreadySolved,
+}
+
+impl Verdict {
+    /// Reads the verdict from the page returned for a submission.
+    pub fn parse(page: &str) -> Result<Self> {
+        if page.contains("That's the right answer") {
+            Ok(Verdict::Right)
+        } else if page.contains("That's not the right answer") {
+            Ok(if page.contains("too high") {
+                Verdict::TooHigh
+            } else if page.contains("too low") {
+                Verdict::TooLow
+            } else {
+                Verdict::Wrong
+            })
+        } else if page.contains("You gave an answer too recently") {
+            Ok(Verdict::Wait(
+                cooldown(page).unwrap_or(Duration::from_secs(60)),
+            ))
+        } else if page.contains("Did you already complete it") {
+            Ok(Verdict::AlreadySolved)
+        } else {
+            Err(Error::Http(
+                "unexpected response to a submission".to_string(),
+            ))
+        }
+    }
+
+    /// Name in the history, `None` for verdicts that say nothing about the answer.
+    fn key(self) -> Option<&'static str> {
+        match self {
+            Verdict::Right => Some("right"),
+            Verdict::Wrong => Some("wrong"),
+            Verdict::TooHigh => Some("too-high"),
+            Verdict::TooLow => Some("too-low"),
+            Verdict::Wait(_) | Verdict::AlreadySolved => None,
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        [
+            Verdict::Right,
+            Verdict::Wrong,
+            Verdict::TooHigh,
+            Verdict::TooLow,
+        ]
+        .into_iter()
+        .find(|verdict| verdict.key() == Some(key))
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Right => write!(f, "right answer"),
+            Verdict::Wrong => write!(f, "wrong answer"),
+            Verdict::TooHigh => write!(f, "wrong answer, too high"),
+            Verdict::TooLow => write!(f, "wrong answer, too low"),
+            Verdict::Wait(left) => write!(f, "too soon, wait {}s", left.as_secs()),
+            Verdict::AlreadySolved => write!(f, "already solved"),
+        }
+    }
+}
+
+/// Pause the page asks for, like `You have 1m 26s left to wait` or
+/// `please wait one minute before trying again`.
+fn cooldown(page: &str) -> Option<Duration> {
+    if let Some((_, rest)) = page.split_once("You have ") {
+        let (left, _) = rest.split_once(" left to wait")?;
+        return left
+            .split_whitespace()
+            .map(|part| {
+                let (value, unit) = part.split_at(part.len().checked_sub(1)?);
+                let value: u64 = value.parse().ok()?;
+                match unit {
+                    "h" => Some(value * 3600),
+                    "m" => Some(value * 60),
+                    "s" => Some(value),
+                    _ => None,
+                }
+            })
+            .sum::<Option<u64>>()
+            .map(Duration::from_secs);
+    }
+    let (_, rest) = page.split_once("please wait ")?;
+    let (wait, _) = rest.split_once(" before trying again")?;
+    let (count, unit) = wait.split_once(' ')?;
+    let count = match count {
+        "one" | "a" => 1,
+        count => count.parse().ok()?,
+    };
+    let unit = if unit.starts_with("minute") { 60 } else { 1 };
+    Some(Duration::from_secs(count * unit))
+}
+
+/// One answer submitted earlier and what the site said about it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    pub day: u8,
+    pub part: u8,
+    pub verdict: Verdict,
+    pub answer: Answer,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct History {
+    pub submissions: Vec<Submission>,
+}
+
+impl History {
+    /// Parses `<day> <part> <verdict> <answer>` lines, skipping blanks and `#` comments.
+    pub fn parse(text: &str) -> Result<Self> {
+        let submissions = text
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .map(|line| {
+                let mut fields = line.trim().splitn(4, char::is_whitespace);
+                let (Some(day), Some(part), Some(verdict), Some(answer)) =
+                    (fields.next(), fields.next(), fields.next(), fields.next())
+                else {
+                    return Err(Error::at(
+                        text,
+                        line,
+                        "expected `<day> <part> <verdict> <answer>`",
+                    ));
+                };
+                Ok(Submission {
+                    day: parse_at(text, day, "a day")?,
+                    part: parse_at(text, part, "a part")?,
+                    verdict: Verdict::from_key(verdict).ok_or_else(|| {
+                        Error::at(text, verdict, format!("unknown verdict `{verdict}`"))
+                    })?,
+                    answer: answer.trim().into(),
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { submissions })
+    }
+
+    /// Reads the history in `dir`, an empty one if nothing was submitted yet.
+    pub fn load(dir: &Path) -> Result<Self> {
+        match input::read(&dir.join(HISTORY))? {
+            Some(text) => Self::parse(&text),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Why `answer` must not be submitted for `part` of `day`, `None` if it may be.
+    pub fn check(&self, day: u8, part: u8, answer: &Answer) -> Option<String> {
+        let value = |answer: &Answer| match answer {
+            Answer::Int(value) => Some(*value),
+            _ => None,
+        };
+        for submission in &self.submissions {
+            if submission.day != day || submission.part != part {
+                continue;
+            }
+            let known = &submission.answer;
+            let bound = value(answer).zip(value(known));
+            let reason = match submission.verdict {
+                Verdict::Right => Some(format!(
+                    "day {day} part {part} is already solved with {known}"
+                )),
+                verdict if known == answer => Some(format!("{answer} was already a {verdict}")),
+                Verdict::TooHigh if bound.is_some_and(|(answer, known)| answer >= known) => {
+                    Some(format!("{answer} is too high, {known} already was"))
+                }
+                Verdict::TooLow if bound.is_some_and(|(answer, known)| answer <= known) => {
+                    Some(format!("{answer} is too low, {known} already was"))
+                }
+                _ => None,
+            };
+            if reason.is_some() {
+                return reason;
+            }
+        }
+        None
+    }
+}
+
+/// Posts answers for a profile, guarded by its history.
+#[derive(Debug)]
+pub struct Submitter {
+    fetcher: Fetcher,
+}
+
+impl Submitter {
+    pub fn new(fetcher: Fetcher) -> Self {
+        Self { fetcher }
+    }
+
+    /// Submits `answer` unless the history or a pending pause rules it out.
+    ///
+    /// Verdicts about the answer go to the history, and right answers to the
+    /// profile's manifest as well.
+    pub fn submit(&self, profile: &Profile, day: u8, part: u8, answer: &Answer) -> Result<Verdict> {
+        let history = History::load(&profile.dir)?;
+        if let Some(reason) = history.check(day, part, answer) {
+            return Err(Error::Refused(reason));
+        }
+        let next_submit = profile.dir.join(COOLDOWN_FILE);
+        if let Some(next) = input::read(&next_submit)? {
+            let next = Duration::from_millis(next.trim().parse().unwrap_or_default());
+            if let Some(left) = next.checked_sub(now()?) {
+                return Err(Error::Refused(format!(
+                    "wait {}s before submitting again",
+                    left.as_secs() + 1
+                )));
+            }
+        }
+
+        let answer_text = answer.to_string();
+        let level = part.to_string();
+        let page = self.fetcher.request(
+            profile,
+            &format!("day/{day}/answer"),
+            &[("level", &level), ("answer", &answer_text)],
+        )?;
+        let verdict = Verdict::parse(&page)?;
+        info!(day, part, %answer, %verdict);
+
+        if let Some(pause) = cooldown(&page) {
+            std::fs::write(&next_submit, (now()? + pause).as_millis().to_string())?;
+        }
+        if let Some(key) = verdict.key() {
+            input::append_line(
+                &profile.dir.join(HISTORY),
+                &format!("{day} {part} {key} {answer}"),
+            )?;
+        }
+        if verdict == Verdict::Right {
+            verify::append_manifest(
+                &profile.dir,
+                &Expected {
+                    day,
+                    part,
+                    input: format!("day{day:02}.txt"),
+                    answer: answer.clone(),
+                    settings: vec![],
+                },
+            )?;
+        }
+        Ok(verdict)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, sync::atomic::Ordering};
+
+    use rstest::*;
+
+    use super::*;
+    use crate::{
+        fetch::{Config, mock::serve},
+        input::{DEFAULT_PROFILE, Scratch},
+    };
+
+    #[rstest]
+    #[case(
+        "<p>That's the right answer!  You are one gold star closer.</p>",
+        Verdict::Right
+    )]
+    #[case(
+        "<p>That's not the right answer; your answer is too high.  please wait one minute before trying again.</p>",
+        Verdict::TooHigh
+    )]
+    #[case(
+        "<p>That's not the right answer; your answer is too low.</p>",
+        Verdict::TooLow
+    )]
+    #[case("<p>That's not the right answer.</p>", Verdict::Wrong)]
+    #[case(
+        "<p>You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 26s left to wait.</p>",
+        Verdict::Wait(Duration::from_secs(86))
+    )]
+    #[case(
+        "<p>You don't seem to be solving the right level.  Did you already complete it?</p>",
+        Verdict::AlreadySolved
+    )]
+    fn test_verdict(#[case] page: &str, #[case] verdict: Verdict) {
+        assert_eq!(Verdict::parse(page).unwrap(), verdict);
+    }
+
+    #[test]
+    fn test_cooldown() {
+        let page = "That's not the right answer.  please wait one minute before trying again.";
+        assert_eq!(cooldown(page), Some(Duration::from_secs(60)));
+        let page = "That's not the right answer.  please wait 5 minutes before trying again.";
+        assert_eq!(cooldown(page), Some(Duration::from_secs(300)));
+        assert_eq!(cooldown("That's the right answer!"), None);
+        assert!(Verdict::parse("<html>maintenance</html>").is_err());
+    }
+
+    #[test]
+    fn test_history() {
+        let history =
+            History::parse("1 1 too-high 100\n1 1 too-low 40\n1 1 wrong abc\n2 1 right 7").unwrap();
+        let check = |day, part, answer: Answer| history.check(day, part, &answer);
+        assert_eq!(check(1, 1, 60.into()), None);
+        assert_eq!(check(1, 2, 100.into()), None);
+        assert_eq!(
+            check(1, 1, 100.into()).unwrap(),
+            "100 was already a wrong answer, too high"
+        );
+        assert_eq!(
+            check(1, 1, 150.into()).unwrap(),
+            "150 is too high, 100 already was"
+        );
+        assert_eq!(
+            check(1, 1, 12.into()).unwrap(),
+            "12 is too low, 40 already was"
+        );
+        assert_eq!(
+            check(1, 1, "abc".into()).unwrap(),
+            "abc was already a wrong answer"
+        );
+        assert_eq!(check(1, 1, "xyz".into()), None);
+        assert_eq!(
+            check(2, 1, 8.into()).unwrap(),
+            "day 2 part 1 is already solved with 7"
+        );
+
+        let err = History::parse("1 1 maybe 5").unwrap_err();
+        assert_eq!(err.to_string(), "1:5: unknown verdict `maybe`");
+    }
+
+    fn submitter(base_url: String, root: &Path) -> Submitter {
+        let config = Config {
+            base_url,
+            sessions: HashMap::from([("default".to_string(), "abc".to_string())]),
+            min_interval: Duration::ZERO,
+        };
+        Submitter::new(Fetcher::new(config, root))
+    }
+
+    #[test]
+    fn test_submit() {
+        let (base_url, requests) = serve(|request| {
+            let answer: i64 = request
+                .body
+                .strip_prefix("level=1&answer=")
+                .and_then(|answer| answer.parse().ok())
+                .unwrap_or_default();
+            let page = match answer.cmp(&42) {
+                _ if request.path != "/2025/day/1/answer" => "not found",
+                std::cmp::Ordering::Equal => "That's the right answer!",
+                std::cmp::Ordering::Less => "That's not the right answer; your answer is too low.",
+                std::cmp::Ordering::Greater => {
+                    "That's not the right answer; your answer is too high."
+                }
+            };
+            ("200 OK", page.to_string())
+        });
+        let root = Scratch::new("submit");
+        let submitter = submitter(base_url, &root);
+        let profile = Profile::new(&root, DEFAULT_PROFILE);
+        let submit = |answer: i64| submitter.submit(&profile, 1, 1, &answer.into());
+
+        assert_eq!(submit(50).unwrap(), Verdict::TooHigh);
+        assert_eq!(submit(10).unwrap(), Verdict::TooLow);
+        assert_eq!(
+            submit(60).unwrap_err().to_string(),
+            "60 is too high, 50 already was"
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        assert_eq!(submit(42).unwrap(), Verdict::Right);
+        assert_eq!(
+            submit(42).unwrap_err().to_string(),
+            "day 1 part 1 is already solved with 42"
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        assert_eq!(
+            std::fs::read_to_string(root.join(HISTORY)).unwrap(),
+            "1 1 too-high 50\n1 1 too-low 10\n1 1 right 42\n"
+        );
+        assert_eq!(
+            verify::load_manifest(&root).unwrap(),
+            vec![Expected {
+                day: 1,
+                part: 1,
+                input: "day01.txt".to_string(),
+                answer: 42.into(),
+                settings: vec![],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_submit_throttled() {
+        let (base_url, requests) = serve(|_| {
+            (
+                "200 OK",
+                "You gave an answer too recently.  You have 30s left to wait.".to_string(),
+            )
+        });
+        let root = Scratch::new("submit-throttled");
+        let submitter = submitter(base_url, &root);
+        let profile = Profile::new(&root, DEFAULT_PROFILE);
+
+        assert_eq!(
+            submitter.submit(&profile, 3, 2, &"5".into()).unwrap(),
+            Verdict::Wait(Duration::from_secs(30))
+        );
+        let err = submitter.submit(&profile, 3, 2, &"6".into()).unwrap_err();
+        assert!(err.to_string().starts_with("wait "), "{err}");
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        assert!(!root.join(HISTORY).exists());
+    }
+}
@@ -0,0 +1,342 @@
+use std::{fmt, path::Path};
+
+use crate::{
+    answer::Answer,
+    error::{Error, Result, parse_at},
+    input::{self, Profile, profiles},
+    solution,
+};
+
+/// Manifest of expected answers, inside the input directory.
+pub const MANIFEST: &str = "answers.txt";
+
+/// Known answer for one part of a day on one input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expected {
+    pub day: u8,
+    pub part: u8,
+    /// Input file relative to the manifest.
+    pub input: String,
+    pub answer: Answer,
+    /// `name=value` settings the input needs, see [`crate::solution::Solution::with_setting`].
+    pub settings: Vec<(String, String)>,
+}
+
+/// Parses `<day> <part> <input> <answer> [<name>=<value>...]` lines, skipping blanks
+/// and `#` comments.
+///
+/// The answer is the rest of the line up to the settings, so text answers may contain
+/// spaces.
+pub fn parse_manifest(text: &str) -> Result<Vec<Expected>> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|line| {
+            let mut fields = line.trim().splitn(4, char::is_whitespace);
+            let (Some(day), Some(part), Some(input), Some(answer)) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                return Err(Error::at(
+                    text,
+                    line,
+                    "expected `<day> <part> <input> <answer>`",
+                ));
+            };
+            let mut answer = answer.trim();
+            let mut settings = vec![];
+            while let Some((rest, last)) = answer.rsplit_once(char::is_whitespace)
+                && let Some((name, value)) = last.split_once('=')
+            {
+                settings.push((name.to_string(), value.to_string()));
+                answer = rest.trim_end();
+            }
+            settings.reverse();
+            Ok(Expected {
+                day: parse_at(text, day, "a day")?,
+                part: parse_at(text, part, "a part")?,
+                input: input.to_string(),
+                answer: answer.into(),
+                settings,
+            })
+        })
+        .collect()
+}
+
+/// Reads the manifest in `dir`, an empty one if there is none yet.
+pub fn load_manifest(dir: &Path) -> Result<Vec<Expected>> {
+    match input::read(&dir.join(MANIFEST))? {
+        Some(text) => parse_manifest(&text),
+        None => Ok(vec![]),
+    }
+}
+
+/// Adds `expected` to the manifest in `dir`.
+pub fn append_manifest(dir: &Path, expected: &Expected) -> Result<()> {
+    let Expected {
+        day,
+        part,
+        input,
+        answer,
+        settings,
+    } = expected;
+    let mut line = format!("{day} {part} {input} {answer}");
+    for (name, value) in settings {
+        line += &format!(" {name}={value}");
+    }
+    input::append_line(&dir.join(MANIFEST), &line)
+}
+
+#[derive(Debug)]
+pub enum Status {
+    Pass,
+    Fail {
+        expected: Answer,
+        got: Answer,
+    },
+    /// No expected answer or no input file to check against.
+    Missing(String),
+    Error(Error),
+}
+
+/// Result of running one part of a day on one input.
+#[derive(Debug)]
+pub struct Check {
+    pub profile: String,
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    pub status: Status,
+}
+
+/// Runs every registered solution against all profiles in `root`.
+pub fn verify(root: &Path) -> Result<Vec<Check>> {
+    let mut checks = vec![];
+    for profile in profiles(root)? {
+        checks.extend(verify_profile(&profile)?);
+    }
+    Ok(checks)
+}
+
+/// Runs every registered solution against the inputs and answers of `profile`.
+///
+/// Parts listed in the manifest are checked on each of their inputs, the rest
+/// run on `dayNN.txt` and are reported missing once they produce an answer.
+pub fn verify_profile(profile: &Profile) -> Result<Vec<Check>> {
+    let dir = &profile.dir;
+    let manifest = load_manifest(dir)?;
+    let mut checks = vec![];
+    for entry in solution::DAYS {
+        let day = entry.day();
+        for part in 1..=2 {
+            let listed: Vec<_> = manifest
+                .iter()
+                .filter(|expected| expected.day == day && expected.part == part)
+                .collect();
+            let inputs = if listed.is_empty() {
+                vec![format!("day{day:02}.txt")]
+            } else {
+                listed
+                    .iter()
+                    .map(|expected| expected.input.clone())
+                    .collect()
+            };
+            for input in inputs {
+                let expected = listed.iter().find(|expected| expected.input == input);
+                let settings = expected.map_or(&[][..], |expected| &expected.settings);
+                let expected = expected.map(|expected| &expected.answer);
+                let status = match input::read(&dir.join(&input)) {
+                    // `new` leaves an empty input until it is pasted or fetched
+                    Ok(None) => Status::Missing("no input".to_string()),
+                    Ok(Some(text)) if text.is_empty() => Status::Missing("no input".to_string()),
+                    Err(err) => Status::Error(err),
+                    Ok(Some(text)) => {
+                        match (entry.solve_setting(part, &text, settings), expected) {
+                            (Ok(None), _) => continue,
+                            (Err(err), _) => Status::Error(err),
+                            (Ok(Some(got)), None) => {
+                                Status::Missing(format!("no answer, got {got}"))
+                            }
+                            (Ok(Some(got)), Some(expected)) if got == *expected => Status::Pass,
+                            (Ok(Some(got)), Some(expected)) => Status::Fail {
+                                expected: expected.clone(),
+                                got,
+                            },
+                        }
+                    }
+                };
+                checks.push(Check {
+                    profile: profile.name.clone(),
+                    day,
+                    part,
+                    input,
+                    status,
+                });
+            }
+        }
+    }
+    Ok(checks)
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail { expected, got } => write!(f, "FAIL expected {expected}, got {got}"),
+            Status::Missing(reason) => write!(f, "missing ({reason})"),
+            Status::Error(err) => write!(f, "ERROR {err}"),
+        }
+    }
+}
+
+/// Asserts `solve` gives the manifest answer for `part` of `day` on every profile's input.
+///
+/// Inputs missing from this checkout are reported and skipped. `solve` runs with the
+/// default config, so inputs with settings go through the registered day instead.
+#[cfg(test)]
+#[track_caller]
+pub(crate) fn assert_answers<A: Into<Answer>>(day: u8, part: u8, solve: impl Fn(&str) -> A) {
+    let mut listed = 0;
+    for profile in profiles(&input::root()).unwrap() {
+        for expected in load_manifest(&profile.dir).unwrap() {
+            if expected.day != day || expected.part != part {
+                continue;
+            }
+            listed += 1;
+            let path = profile.dir.join(&expected.input);
+            let Some(input) = input::read(&path).unwrap() else {
+                eprintln!("skipped: input missing ({})", path.display());
+                continue;
+            };
+            let got = if expected.settings.is_empty() {
+                solve(&input).into()
+            } else {
+                let entry = solution::find(day).unwrap();
+                let got = entry.solve_setting(part, &input, &expected.settings);
+                got.unwrap().unwrap()
+            };
+            assert_eq!(
+                got,
+                expected.answer,
+                "day {day} part {part} on {}",
+                path.display()
+            );
+        }
+    }
+    assert!(listed > 0, "no answers for day {day} part {part}");
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::input::{DEFAULT_PROFILE, Scratch};
+
+    #[test]
+    fn test_parse_manifest() {
+        let text = "# day part input answer\n1 1 day01.txt 984\n\n12 2 extra/day12.txt Merry Christmas\n\
+            8 1 day08.txt 40 connections=10\n";
+        assert_eq!(
+            parse_manifest(text).unwrap(),
+            vec![
+                Expected {
+                    day: 1,
+                    part: 1,
+                    input: "day01.txt".to_string(),
+                    answer: 984.into(),
+                    settings: vec![],
+                },
+                Expected {
+                    day: 12,
+                    part: 2,
+                    input: "extra/day12.txt".to_string(),
+                    answer: "Merry Christmas".into(),
+                    settings: vec![],
+                },
+                Expected {
+                    day: 8,
+                    part: 1,
+                    input: "day08.txt".to_string(),
+                    answer: 40.into(),
+                    settings: vec![("connections".to_string(), "10".to_string())],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_manifest_errors() {
+        let err = parse_manifest("1 1 day01.txt 984\n2 1 day02.txt").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "2:1: expected `<day> <part> <input> <answer>`"
+        );
+        let err = parse_manifest("x 1 day01.txt 984").unwrap_err();
+        assert_eq!(err.to_string(), "1:1: expected a day, found `x`");
+    }
+
+    #[test]
+    fn test_verify() {
+        let dir = Scratch::new("verify");
+        std::fs::write(
+            dir.join("day01.txt"),
+            "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82",
+        )
+        .unwrap();
+        std::fs::write(dir.join("day02.txt"), "11-x").unwrap();
+        let boxes = "0,0,0\n1,0,0\n5,0,0\n7,0,0";
+        std::fs::write(dir.join("day08.txt"), boxes).unwrap();
+        std::fs::write(
+            dir.join(MANIFEST),
+            "1 1 day01.txt 3\n1 2 day01.txt 7\n8 1 day08.txt 2 links=1\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(dir.join("alice")).unwrap();
+        std::fs::write(dir.join("alice/day01.txt"), "R50\nL1").unwrap();
+        std::fs::write(dir.join("alice/day08.txt"), boxes).unwrap();
+        std::fs::write(
+            dir.join("alice").join(MANIFEST),
+            "1 1 day01.txt 1\n8 1 day08.txt 2 connections=1\n",
+        )
+        .unwrap();
+
+        let profiles = profiles(&dir).unwrap();
+        let missing = verify(&dir.join("missing")).unwrap();
+        let checks = verify(&dir).unwrap();
+
+        assert_eq!(
+            profiles,
+            vec![
+                Profile::new(&dir, DEFAULT_PROFILE),
+                Profile {
+                    name: "alice".to_string(),
+                    dir: dir.join("alice"),
+                }
+            ]
+        );
+        let status = |profile, day, part| {
+            let check = checks
+                .iter()
+                .find(|check| check.profile == profile && check.day == day && check.part == part)
+                .unwrap();
+            check.status.to_string()
+        };
+        assert_eq!(status("default", 1, 1), "pass");
+        assert_eq!(status("default", 1, 2), "FAIL expected 7, got 6");
+        assert_eq!(
+            status("default", 2, 1),
+            "ERROR 1:4: expected a number, found `x`"
+        );
+        assert_eq!(status("default", 3, 1), "missing (no input)");
+        assert_eq!(status("alice", 1, 1), "pass");
+        assert_eq!(status("alice", 1, 2), "missing (no answer, got 1)");
+        assert_eq!(
+            status("default", 8, 1),
+            "ERROR day 8 has no setting `links`"
+        );
+        assert_eq!(status("alice", 8, 1), "pass");
+        assert_eq!(checks.len(), 2 * solution::DAYS.len() * 2);
+        assert!(
+            missing
+                .iter()
+                .all(|check| check.status.to_string() == "missing (no input)")
+        );
+    }
+}
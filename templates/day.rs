@@ -0,0 +1,55 @@
+use crate::{
+    answer::Answer,
+    error::{Error, Result},
+    solution::Solution,
+};
+
+pub fn process_part1(_input: &str) -> Result<u64> {
+    Err(Error::puzzle("part 1 is not solved yet"))
+}
+
+pub fn process_part2(_input: &str) -> Result<u64> {
+    Err(Error::puzzle("part 2 is not solved yet"))
+}
+
+pub struct Day{{NN}};
+
+impl Solution for Day{{NN}} {
+    const DAY: u8 = {{DAY}};
+    type Input<'a> = &'a str;
+    type Config = ();
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(input)
+    }
+
+    fn part1(input: &Self::Input<'_>, _: &()) -> Result<Answer> {
+        Ok(process_part1(input)?.into())
+    }
+
+    fn part2(input: &Self::Input<'_>, _: &()) -> Result<Option<Answer>> {
+        Ok(Some(process_part2(input)?.into()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::verify::assert_answers;
+
+    #[test_log::test]
+    #[ignore = "example not filled in yet"]
+    fn test_part1() {
+        let input = "";
+        assert_eq!(process_part1(input).unwrap(), 0);
+        assert_answers({{DAY}}, 1, |input| process_part1(input).unwrap());
+    }
+
+    #[test_log::test]
+    #[ignore = "example not filled in yet"]
+    fn test_part2() {
+        let input = "";
+        assert_eq!(process_part2(input).unwrap(), 0);
+        assert_answers({{DAY}}, 2, |input| process_part2(input).unwrap());
+    }
+}